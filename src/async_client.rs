@@ -0,0 +1,512 @@
+//! Non-blocking client built on `reqwest::Client`
+//!
+//! Mirrors the blocking [`Client`](crate::client::Client) and shares its
+//! `ClientOptions`, `Error` type and `Result` alias.
+
+use chrono::{DateTime, Utc};
+use crate::client::{content_type, parse_body, ApiResponse, ClientOptions, PreparedRequest, TokenRequest, REDACTED_AUTHORIZATION};
+use crate::token::Token;
+use crate::error::*;
+use crate::nodes::FulfillmentNode;
+use crate::dedupe::{request_key, RecentRequests};
+use crate::rate_limit::RateLimiter;
+use crate::retry;
+use crate::trace;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+pub use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::Mutex;
+
+/// Cloning is cheap, clones share the token, rate limit and connection pool
+#[derive(Clone)]
+pub struct AsyncClient {
+  options: Arc<ClientOptions>,
+  /// Held while fetching a new token so only one task refreshes at a time
+  refresh: Arc<Mutex<()>>,
+  limiter: Option<Arc<RateLimiter>>,
+  recent: Option<Arc<RecentRequests>>,
+  /// Fulfillment nodes cached by `fulfillment_nodes`
+  pub(crate) nodes: Arc<Mutex<Option<Arc<[FulfillmentNode]>>>>,
+  client: reqwest::Client,
+}
+
+impl AsyncClient {
+  pub fn new(opts: ClientOptions) -> Result<AsyncClient> {
+    opts.check_credentials()?;
+    opts.check_rate_limit()?;
+    let mut builder = reqwest::Client::builder().gzip(opts.gzip);
+    if let Some(timeout) = opts.timeout {
+      builder = builder.timeout(timeout);
+    }
+    if let Some(ref proxy) = opts.proxy {
+      builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+    if let Some(max_idle) = opts.pool_max_idle_per_host {
+      builder = builder.pool_max_idle_per_host(max_idle);
+    }
+    if let Some(idle_timeout) = opts.pool_idle_timeout {
+      builder = builder.pool_idle_timeout(idle_timeout);
+    }
+    Ok(AsyncClient {
+      client: builder.build()?,
+      limiter: opts.rate_limit.map(|rate| Arc::new(RateLimiter::new(rate))),
+      recent: opts.dedupe_window.map(|window| Arc::new(RecentRequests::new(window))),
+      options: Arc::new(opts),
+      refresh: Arc::new(Mutex::new(())),
+      nodes: Arc::new(Mutex::new(None)),
+    })
+  }
+
+  /// Builds a client from `ClientOptions::from_env`
+  pub fn from_env() -> Result<AsyncClient> {
+    AsyncClient::new(ClientOptions::from_env()?)
+  }
+
+  pub fn with_http_client(opts: ClientOptions, http_client: reqwest::Client) -> Result<AsyncClient> {
+    opts.check_credentials()?;
+    opts.check_rate_limit()?;
+    Ok(AsyncClient {
+      limiter: opts.rate_limit.map(|rate| Arc::new(RateLimiter::new(rate))),
+      recent: opts.dedupe_window.map(|window| Arc::new(RecentRequests::new(window))),
+      options: Arc::new(opts),
+      refresh: Arc::new(Mutex::new(())),
+      nodes: Arc::new(Mutex::new(None)),
+      client: http_client,
+    })
+  }
+
+  /// The merchant ID this client was configured with. No endpoint
+  /// currently needs it, Jet identifies the merchant from the auth token.
+  pub fn merchant_id(&self) -> &str {
+    &self.options.merchant_id
+  }
+
+  /// When the cached token expires, or `None` if no token was fetched yet
+  pub fn token_expires_on(&self) -> Option<DateTime<Utc>> {
+    self.options.token_store.load().map(|token| token.expires_on)
+  }
+
+  /// Fetches a new token even if the cached one is still valid
+  pub async fn refresh_token(&self) -> Result<()> {
+    let _refresh = self.refresh.lock().await;
+    let token = self.get_token().await?;
+    self.options.token_store.save(&token);
+    Ok(())
+  }
+
+  /// Returns a valid bearer token, fetching a new one if needed.
+  pub(crate) async fn bearer_token(&self) -> Result<String> {
+    let store = &self.options.token_store;
+    if let Some(token) = store.load().filter(Token::is_fresh) {
+      return Ok(token.id_token);
+    }
+
+    let _refresh = self.refresh.lock().await;
+
+    // another task may have refreshed while we waited for the lock
+    if let Some(token) = store.load().filter(Token::is_fresh) {
+      return Ok(token.id_token);
+    }
+
+    let token = self.get_token().await?;
+    store.save(&token);
+    Ok(token.id_token)
+  }
+
+  async fn get_token(&self) -> Result<Token> {
+    let started = Instant::now();
+    let res = self
+      .client
+      .post(format!("{}/token", self.options.api_endpoint()))
+      .headers(self.options.base_headers()?)
+      .json(&TokenRequest {
+        user: &self.options.api_user,
+        pass: &self.options.secret,
+      })
+      .send()
+      .await
+      .inspect_err(|err| {
+        trace::send_error(&Method::POST, "/token", err, started.elapsed());
+      })?;
+
+    let status = res.status();
+    trace::response(&Method::POST, "/token", status, started.elapsed(), 0);
+    if !status.is_success() {
+      let body = res.text().await?;
+      return Err(Error::GetTokenRequest { status, body });
+    }
+
+    res.json().await.map_err(Into::into)
+  }
+
+  async fn send<F>(&self, method: Method, path: &str, f: F) -> Result<Response>
+  where
+    F: Fn(RequestBuilder) -> RequestBuilder,
+  {
+    use headers::{HeaderMapExt, Authorization};
+
+    let mut attempt = 0;
+    let mut refreshed = false;
+    let first_attempt = Instant::now();
+    loop {
+      let token = self.bearer_token().await?;
+      let mut req = self
+        .client
+        .request(method.clone(), format!("{}{}", self.options.api_endpoint(), path));
+      req = req.headers({
+        let mut map = self.options.base_headers()?;
+        map.typed_insert(Authorization::bearer(&token).map_err(|_| Error::InvalidBearerToken)?);
+        map
+      });
+
+      if let Some(ref limiter) = self.limiter {
+        tokio::time::sleep(limiter.reserve()).await;
+      }
+
+      let started = Instant::now();
+      let res = f(req).send().await.inspect_err(|err| {
+        trace::send_error(&method, path, err, started.elapsed());
+      })?;
+      let status = res.status();
+      trace::response(&method, path, status, started.elapsed(), attempt);
+
+      if status.is_success() {
+        return Ok(res);
+      }
+
+      // The token may have expired since it was checked, refresh it and
+      // retry once
+      if status == StatusCode::UNAUTHORIZED && !refreshed {
+        refreshed = true;
+        self.refresh_token().await?;
+        continue;
+      }
+
+      if attempt < self.options.max_retries && retry::is_transient(status) {
+        let delay = retry::delay(res.headers(), self.options.base_backoff, attempt);
+        if retry::within_budget(first_attempt.elapsed(), delay, self.options.max_total_retry_duration) {
+          attempt += 1;
+          tokio::time::sleep(delay).await;
+          continue;
+        }
+      }
+
+      let content_type = content_type(res.headers()).map(ToOwned::to_owned);
+      let body = res.text().await?;
+      let err = Error::from_response(path, status, content_type.as_deref(), body);
+      if attempt > 0 {
+        return Err(Error::ExhaustedRetries { attempts: attempt + 1, last: Box::new(err) });
+      }
+      return Err(err);
+    }
+  }
+
+  /// Builds the request `send` would make, without a token or sending it
+  pub(crate) fn prepare<F>(&self, method: Method, path: &str, f: F) -> Result<PreparedRequest>
+  where
+    F: Fn(RequestBuilder) -> RequestBuilder,
+  {
+    let mut headers = self.options.base_headers()?;
+    headers.insert(AUTHORIZATION, HeaderValue::from_static(REDACTED_AUTHORIZATION));
+    let req = f(self
+      .client
+      .request(method, format!("{}{}", self.options.api_endpoint(), path))
+      .headers(headers))
+    .build()?;
+    Ok(PreparedRequest::new(
+      req.method().clone(),
+      req.url().to_string(),
+      req.headers().clone(),
+      req.body().and_then(|body| body.as_bytes()),
+    ))
+  }
+
+  pub(crate) async fn request<T, F>(&self, method: Method, path: &str, f: F) -> Result<T>
+  where
+    T: DeserializeOwned,
+    F: Fn(RequestBuilder) -> RequestBuilder,
+  {
+    let res = self.send(method, path, f).await?;
+    let status = res.status();
+    let headers = res.headers().clone();
+    parse_body(path, status, &headers, &res.bytes().await?)
+  }
+
+  pub(crate) async fn request_full<T, F>(&self, method: Method, path: &str, f: F) -> Result<ApiResponse<T>>
+  where
+    T: DeserializeOwned,
+    F: Fn(RequestBuilder) -> RequestBuilder,
+  {
+    let res = self.send(method, path, f).await?;
+    let status = res.status();
+    let headers = res.headers().clone();
+    Ok(ApiResponse {
+      value: parse_body(path, status, &headers, &res.bytes().await?)?,
+      status,
+      headers,
+    })
+  }
+
+  pub(crate) async fn request_no_content<F>(&self, method: Method, path: &str, f: F) -> Result<()>
+  where
+    F: Fn(RequestBuilder) -> RequestBuilder,
+  {
+    self.send(method, path, f).await?;
+    Ok(())
+  }
+
+  /// Sends `body` as JSON unless the same request succeeded within
+  /// `ClientOptions::dedupe_window`
+  pub(crate) async fn request_deduped<B>(&self, method: Method, path: &str, body: &B) -> Result<()>
+  where
+    B: Serialize,
+  {
+    let key = match self.recent {
+      Some(ref recent) => {
+        let key = request_key(method.as_str(), path, &serde_json::to_string(body)?);
+        if !recent.reserve(&key) {
+          return Ok(());
+        }
+        Some(key)
+      }
+      None => None,
+    };
+    let res = self.request_no_content(method, path, |req| req.json(body)).await;
+    if let (Some(recent), Some(key)) = (self.recent.as_ref(), key) {
+      match res {
+        Ok(()) => recent.complete(key),
+        Err(_) => recent.release(&key),
+      }
+    }
+    res
+  }
+
+  /// Like `request_no_content` but returns the response headers
+  pub(crate) async fn request_headers<F>(&self, method: Method, path: &str, f: F) -> Result<HeaderMap>
+  where
+    F: Fn(RequestBuilder) -> RequestBuilder,
+  {
+    let res = self.send(method, path, f).await?;
+    Ok(res.headers().clone())
+  }
+}
+
+#[cfg(test)]
+pub(crate) fn get_mock_async_client(server: &mockito::Server) -> AsyncClient {
+  AsyncClient::new(ClientOptions {
+    api_user: "mock-user".to_owned(),
+    secret: "mock-secret".to_owned(),
+    endpoint: Some(server.url()),
+    ..Default::default()
+  }).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::client::MOCK_TOKEN;
+  use crate::orders::OrderStatus;
+  use std::time::Duration as StdDuration;
+
+  #[test]
+  fn test_empty_credentials() {
+    let res = AsyncClient::new(ClientOptions::default());
+    assert!(matches!(res, Err(Error::InvalidCredentials { field: "api_user" })));
+
+    let res = AsyncClient::with_http_client(ClientOptions::default(), reqwest::Client::new());
+    assert!(matches!(res, Err(Error::InvalidCredentials { field: "api_user" })));
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn test_concurrent_requests_share_token() {
+    let mut server = mockito::Server::new_async().await;
+    let token_mock = server
+      .mock("POST", "/token")
+      .with_body(MOCK_TOKEN)
+      .expect(1)
+      .create_async()
+      .await;
+    server
+      .mock("GET", "/orders/ready")
+      .match_header("authorization", "Bearer mock-token")
+      .with_body(r#"{"order_urls":["/orders/withoutShipmentDetail/aa01"]}"#)
+      .create_async()
+      .await;
+    server
+      .mock("GET", "/returns/created")
+      .match_header("authorization", "Bearer mock-token")
+      .with_body(r#"{"return_urls":[]}"#)
+      .create_async()
+      .await;
+    server
+      .mock("GET", "/settlement/7")
+      .match_header("authorization", "Bearer mock-token")
+      .with_body(r#"{"settlement_report_urls":["/settlement/report/s1"]}"#)
+      .create_async()
+      .await;
+    server
+      .mock("GET", "/setup/fulfillmentNodes")
+      .match_header("authorization", "Bearer mock-token")
+      .with_body(r#"[{ "fulfillment_node_id": "n1", "fulfillment_node_name": "Hoboken", "address": null }]"#)
+      .create_async()
+      .await;
+
+    let client = get_mock_async_client(&server);
+    let (orders, returns, settlements, nodes) = futures_util::join!(
+      client.get_orders(OrderStatus::Ready),
+      client.get_returns(),
+      client.get_settlement_report_ids(7),
+      client.fulfillment_nodes(),
+    );
+    assert_eq!(orders.unwrap().order_urls, ["/orders/withoutShipmentDetail/aa01"]);
+    assert!(returns.unwrap().return_urls.is_empty());
+    assert_eq!(settlements.unwrap(), ["s1"]);
+    assert_eq!(nodes.unwrap()[0].fulfillment_node_id, "n1");
+
+    let clone = client.clone();
+    let tasks: Vec<_> = (0..8)
+      .map(|_| {
+        let client = clone.clone();
+        tokio::spawn(async move { client.get_orders(OrderStatus::Ready).await })
+      })
+      .collect();
+    for task in tasks {
+      task.await.unwrap().unwrap();
+    }
+    token_mock.assert_async().await;
+  }
+
+  #[tokio::test]
+  async fn test_unauthorized_refreshes_token_once() {
+    let mut server = mockito::Server::new_async().await;
+    let token_mock = server
+      .mock("POST", "/token")
+      .with_body(MOCK_TOKEN)
+      .expect(2)
+      .create_async()
+      .await;
+    let unauthorized_mock = server
+      .mock("GET", "/orders/ready")
+      .with_status(401)
+      .expect(1)
+      .create_async()
+      .await;
+    // mockito prefers the 401 mock until its expected hit count is reached
+    server
+      .mock("GET", "/orders/ready")
+      .with_body(r#"{"order_urls":[]}"#)
+      .create_async()
+      .await;
+
+    let res = get_mock_async_client(&server).get_orders(OrderStatus::Ready).await.unwrap();
+    assert!(res.order_urls.is_empty());
+    unauthorized_mock.assert_async().await;
+    token_mock.assert_async().await;
+
+    let mut server = mockito::Server::new_async().await;
+    server.mock("POST", "/token").with_body(MOCK_TOKEN).create_async().await;
+    let unauthorized_mock = server
+      .mock("GET", "/orders/ready")
+      .with_status(401)
+      .expect(2)
+      .create_async()
+      .await;
+    let err = get_mock_async_client(&server)
+      .request_no_content(Method::GET, "/orders/ready", std::convert::identity)
+      .await
+      .unwrap_err();
+    assert_eq!(err.status_code(), Some(StatusCode::UNAUTHORIZED));
+    unauthorized_mock.assert_async().await;
+  }
+
+  #[tokio::test]
+  async fn test_exhausted_retries() {
+    let mut server = mockito::Server::new_async().await;
+    server.mock("POST", "/token").with_body(MOCK_TOKEN).create_async().await;
+    let orders_mock = server
+      .mock("GET", "/orders/ready")
+      .with_status(503)
+      .expect(3)
+      .create_async()
+      .await;
+
+    let client = AsyncClient::new(ClientOptions {
+      api_user: "mock-user".to_owned(),
+      secret: "mock-secret".to_owned(),
+      endpoint: Some(server.url()),
+      max_retries: 2,
+      base_backoff: StdDuration::from_millis(1),
+      ..Default::default()
+    }).unwrap();
+    match client.request_no_content(Method::GET, "/orders/ready", std::convert::identity).await {
+      Err(Error::ExhaustedRetries { attempts: 3, ref last }) => {
+        assert_eq!(last.status_code(), Some(StatusCode::SERVICE_UNAVAILABLE));
+      }
+      other => panic!("unexpected result: {:?}", other),
+    }
+    orders_mock.assert_async().await;
+
+    let retried_mock = server
+      .mock("GET", "/orders/complete")
+      .with_status(503)
+      .expect(1)
+      .create_async()
+      .await;
+    server
+      .mock("GET", "/orders/complete")
+      .with_body(r#"{"order_urls":[]}"#)
+      .create_async()
+      .await;
+    let res = client.get_orders(OrderStatus::Complete).await.unwrap();
+    assert!(res.order_urls.is_empty());
+    retried_mock.assert_async().await;
+  }
+
+  #[tokio::test]
+  async fn test_retry_budget() {
+    let mut server = mockito::Server::new_async().await;
+    server.mock("POST", "/token").with_body(MOCK_TOKEN).create_async().await;
+    let retried_mock = server
+      .mock("GET", "/orders/ready")
+      .with_status(503)
+      .with_header("retry-after", "0")
+      .expect(1)
+      .create_async()
+      .await;
+    // mockito prefers the first mock until its expected hit count is reached
+    let over_budget_mock = server
+      .mock("GET", "/orders/ready")
+      .with_status(503)
+      .with_header("retry-after", "10")
+      .expect(2)
+      .create_async()
+      .await;
+
+    let client = AsyncClient::new(ClientOptions {
+      api_user: "mock-user".to_owned(),
+      secret: "mock-secret".to_owned(),
+      endpoint: Some(server.url()),
+      max_retries: 5,
+      max_total_retry_duration: Some(StdDuration::from_secs(1)),
+      ..Default::default()
+    }).unwrap();
+    match client.request_no_content(Method::GET, "/orders/ready", std::convert::identity).await {
+      Err(Error::ExhaustedRetries { attempts: 2, ref last }) => {
+        assert_eq!(last.status_code(), Some(StatusCode::SERVICE_UNAVAILABLE));
+      }
+      other => panic!("unexpected result: {:?}", other),
+    }
+    retried_mock.assert_async().await;
+
+    // the budget stops the first retry, so the error is returned as is
+    let err = client
+      .request_no_content(Method::GET, "/orders/ready", std::convert::identity)
+      .await
+      .unwrap_err();
+    assert!(matches!(err, Error::Request { .. }), "{:?}", err);
+    over_budget_mock.assert_async().await;
+  }
+}
@@ -0,0 +1,202 @@
+//! Implements Bulk Upload API
+//! [Jet Documentation](https://developer.jet.com/docs/bulk-product-upload)
+//!
+
+use super::client::{Client, Method};
+use chrono::{DateTime, Utc};
+use crate::error::*;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Kind of data contained in a bulk file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FileType {
+  #[serde(rename = "MerchantSKUs")]
+  MerchantSkus,
+  Price,
+  Inventory,
+  Variation,
+  Archive,
+}
+
+/// Where to upload a bulk file, returned by `get_upload_url`
+#[derive(Debug, Deserialize)]
+pub struct UploadToken {
+  /// Pre-signed URL the gzipped file is PUT to
+  pub url: String,
+  pub jet_file_id: String,
+  pub expires_in_seconds: Option<u64>,
+}
+
+/// A bulk file registered with Jet, used to poll its processing status
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkUploadHandle {
+  pub jet_file_id: String,
+}
+
+/// Processing status of a bulk file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileStatus {
+  pub jet_file_id: String,
+  pub file_name: String,
+  pub file_type: FileType,
+  /// One of 'Acknowledged', 'Processing', 'Processed with errors',
+  /// 'Processed successfully'
+  pub status: String,
+  pub file_upload_date: Option<DateTime<Utc>>,
+  pub processing_start: Option<DateTime<Utc>>,
+  pub processing_end: Option<DateTime<Utc>>,
+  pub total_processed: Option<i64>,
+  pub error_count: Option<i64>,
+  /// A sample of the errors found while processing the file
+  pub error_excerpt: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+struct FileUploaded<'a> {
+  url: &'a str,
+  file_type: FileType,
+  file_name: &'a str,
+}
+
+/// The upload URL up to the query string, which holds the signature
+fn without_query(url: &str) -> &str {
+  url.split('?').next().unwrap_or(url)
+}
+
+pub(crate) fn gzip(data: &[u8]) -> Result<Vec<u8>> {
+  let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+  encoder.write_all(data)?;
+  encoder.finish().map_err(Into::into)
+}
+
+impl Client {
+  pub fn get_upload_url(&self) -> Result<UploadToken> {
+    self.request(Method::GET, "/files/uploadToken", std::convert::identity)
+  }
+
+  /// Uploads a bulk file to the token's URL and tells Jet to process it.
+  /// Files not ending in `.gz` are gzipped before uploading.
+  pub fn upload_file(&self, token: &UploadToken, path: &Path, file_type: FileType) -> Result<BulkUploadHandle> {
+    let data = fs::read(path)?;
+    let file_name = path
+      .file_name()
+      .map(|name| name.to_string_lossy().into_owned())
+      .unwrap_or_default();
+    if file_name.ends_with(".gz") {
+      self.upload_bulk_data(token, data, file_type, &file_name)
+    } else {
+      self.upload_bulk_data(token, gzip(&data)?, file_type, &format!("{}.gz", file_name))
+    }
+  }
+
+  pub fn get_file_status(&self, file_id: &str) -> Result<FileStatus> {
+    self.request(
+      Method::GET,
+      &format!("/files/{}", file_id),
+      std::convert::identity,
+    )
+  }
+
+  /// Uploads gzipped `data` to the token's URL and tells Jet to process it
+  pub(crate) fn upload_bulk_data(
+    &self,
+    token: &UploadToken,
+    data: Vec<u8>,
+    file_type: FileType,
+    file_name: &str,
+  ) -> Result<BulkUploadHandle> {
+    let mut res = self
+      .http_client()
+      .put(&token.url)
+      .header("x-ms-blob-type", "BlockBlob")
+      .body(data)
+      .send()
+      .map_err(reqwest::Error::without_url)?;
+
+    if !res.status().is_success() {
+      let mut body = String::new();
+      res.read_to_string(&mut body)?;
+      return Err(Error::Request { path: without_query(&token.url).to_owned(), status: res.status(), body });
+    }
+
+    self.request(
+      Method::POST,
+      "/files/uploaded",
+      |req| {
+        req.json(&FileUploaded {
+          url: &token.url,
+          file_type,
+          file_name,
+        })
+      },
+    )
+  }
+}
+
+#[test]
+fn test_get_file_status() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", "/files/f1")
+    .with_body(r#"{
+      "jet_file_id": "f1",
+      "file_name": "skus.json.gz",
+      "file_type": "MerchantSKUs",
+      "status": "Processed with errors",
+      "file_upload_date": "2017-03-14T12:34:56Z",
+      "total_processed": 10,
+      "error_count": 1,
+      "error_excerpt": ["test_product: missing brand"]
+    }"#)
+    .create();
+
+  let status = get_mock_client(&server).get_file_status("f1").unwrap();
+  assert_eq!(status.file_type, FileType::MerchantSkus);
+  assert_eq!(status.error_count, Some(1));
+}
+
+#[test]
+fn test_upload_file() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server.mock("PUT", "/blob/ok.json.gz?sig=secret").with_status(201).create();
+  server
+    .mock("PUT", "/blob/denied.json.gz?sig=secret")
+    .with_status(403)
+    .with_body("AuthenticationFailed")
+    .create();
+  server
+    .mock("POST", "/files/uploaded")
+    .with_body(r#"{"jet_file_id":"f1"}"#)
+    .create();
+
+  let path = std::env::temp_dir().join(format!("jet-bulk-{}.json", std::process::id()));
+  fs::write(&path, b"{}").unwrap();
+  let client = get_mock_client(&server);
+  let token = |name: &str| UploadToken {
+    url: format!("{}/blob/{}.json.gz?sig=secret", server.url(), name),
+    jet_file_id: "f1".to_owned(),
+    expires_in_seconds: None,
+  };
+
+  let handle = client.upload_file(&token("ok"), &path, FileType::MerchantSkus);
+  let denied = client.upload_file(&token("denied"), &path, FileType::MerchantSkus);
+  fs::remove_file(&path).unwrap();
+
+  assert_eq!(handle.unwrap().jet_file_id, "f1");
+  let err = denied.unwrap_err();
+  assert!(!err.to_string().contains("sig=secret"), "{}", err);
+  match err {
+    Error::Request { ref path, .. } => assert_eq!(*path, format!("{}/blob/denied.json.gz", server.url())),
+    other => panic!("unexpected error: {:?}", other),
+  }
+}
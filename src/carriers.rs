@@ -0,0 +1,150 @@
+//! Shipping carriers accepted by Jet
+//! [Jet Documentation](https://developer.jet.com/docs/ship-order)
+//!
+
+use crate::utils::encode_path_segment;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// Carrier of a shipment. Names are matched case-insensitively, carriers
+/// this crate doesn't know are kept in `Other`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Carrier {
+  /// 'UPS'
+  Ups,
+  /// 'UPS Mail Innovations'
+  UpsMailInnovations,
+  /// 'UPS SurePost'
+  UpsSurePost,
+  /// 'FedEx'
+  FedEx,
+  /// 'FedEx SmartPost'
+  FedExSmartPost,
+  /// 'FedEx Ground'
+  FedExGround,
+  /// 'USPS'
+  Usps,
+  /// 'DHL'
+  Dhl,
+  /// 'DHL Global Mail'
+  DhlGlobalMail,
+  /// 'OnTrac'
+  OnTrac,
+  /// 'Lasership'
+  LaserShip,
+  Other(String),
+}
+
+const KNOWN: &[Carrier] = &[
+  Carrier::Ups,
+  Carrier::UpsMailInnovations,
+  Carrier::UpsSurePost,
+  Carrier::FedEx,
+  Carrier::FedExSmartPost,
+  Carrier::FedExGround,
+  Carrier::Usps,
+  Carrier::Dhl,
+  Carrier::DhlGlobalMail,
+  Carrier::OnTrac,
+  Carrier::LaserShip,
+];
+
+impl Carrier {
+  pub fn as_str(&self) -> &str {
+    match *self {
+      Carrier::Ups => "UPS",
+      Carrier::UpsMailInnovations => "UPS Mail Innovations",
+      Carrier::UpsSurePost => "UPS SurePost",
+      Carrier::FedEx => "FedEx",
+      Carrier::FedExSmartPost => "FedEx SmartPost",
+      Carrier::FedExGround => "FedEx Ground",
+      Carrier::Usps => "USPS",
+      Carrier::Dhl => "DHL",
+      Carrier::DhlGlobalMail => "DHL Global Mail",
+      Carrier::OnTrac => "OnTrac",
+      Carrier::LaserShip => "Lasership",
+      Carrier::Other(ref value) => value,
+    }
+  }
+
+  /// Public tracking page of a shipment, `None` for `Other` carriers
+  pub fn tracking_url(&self, tracking_number: &str) -> Option<String> {
+    let prefix = match *self {
+      Carrier::Ups | Carrier::UpsMailInnovations | Carrier::UpsSurePost => "https://www.ups.com/track?tracknum=",
+      Carrier::FedEx | Carrier::FedExSmartPost | Carrier::FedExGround => "https://www.fedex.com/fedextrack/?trknbr=",
+      Carrier::Usps => "https://tools.usps.com/go/TrackConfirmAction?tLabels=",
+      Carrier::Dhl => "https://www.dhl.com/en/express/tracking.html?AWB=",
+      Carrier::DhlGlobalMail => "https://webtrack.dhlglobalmail.com/?trackingnumber=",
+      Carrier::OnTrac => "https://www.ontrac.com/tracking/?number=",
+      Carrier::LaserShip => "https://www.lasership.com/track/",
+      Carrier::Other(_) => return None,
+    };
+    Some(format!("{}{}", prefix, encode_path_segment(tracking_number.trim())))
+  }
+}
+
+impl fmt::Display for Carrier {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl FromStr for Carrier {
+  type Err = Infallible;
+
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    Ok(Carrier::from(value.to_owned()))
+  }
+}
+
+impl From<String> for Carrier {
+  fn from(value: String) -> Self {
+    KNOWN
+      .iter()
+      .find(|known| known.as_str().eq_ignore_ascii_case(value.trim()))
+      .cloned()
+      .unwrap_or(Carrier::Other(value))
+  }
+}
+
+impl<'a> From<&'a str> for Carrier {
+  fn from(value: &'a str) -> Self {
+    Carrier::from(value.to_owned())
+  }
+}
+
+impl From<Carrier> for String {
+  fn from(value: Carrier) -> Self {
+    match value {
+      Carrier::Other(value) => value,
+      known => known.as_str().to_owned(),
+    }
+  }
+}
+
+#[test]
+fn test_carrier_parse() {
+  assert_eq!("UPs".parse::<Carrier>().unwrap(), Carrier::Ups);
+  assert_eq!(Carrier::from("fedex smartpost"), Carrier::FedExSmartPost);
+  assert_eq!(Carrier::from("Pilot"), Carrier::Other("Pilot".to_owned()));
+  assert_eq!(serde_json::to_string(&Carrier::UpsSurePost).unwrap(), r#""UPS SurePost""#);
+  assert_eq!(serde_json::from_str::<Carrier>(r#""USPS""#).unwrap(), Carrier::Usps);
+  for carrier in KNOWN {
+    assert_eq!(&Carrier::from(carrier.to_string()), carrier);
+  }
+}
+
+#[test]
+fn test_tracking_url() {
+  assert_eq!(
+    Carrier::Ups.tracking_url("1Z12342452342").unwrap(),
+    "https://www.ups.com/track?tracknum=1Z12342452342"
+  );
+  assert_eq!(
+    Carrier::Usps.tracking_url(" 9400 1000 ").unwrap(),
+    "https://tools.usps.com/go/TrackConfirmAction?tLabels=9400%201000"
+  );
+  assert_eq!(Carrier::Other("Pilot".to_owned()).tracking_url("123"), None);
+}
@@ -1,80 +1,445 @@
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Utc};
 use crate::error::*;
+use crate::nodes::FulfillmentNode;
+use crate::dedupe::{request_key, RecentRequests};
+use crate::rate_limit::RateLimiter;
+use crate::retry;
+use crate::trace;
+pub use crate::token::{FileTokenStore, InMemoryTokenStore, Token, TokenStore};
 use reqwest;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE, USER_AGENT};
 pub use reqwest::{Method, blocking::RequestBuilder, blocking::Response, StatusCode};
 use serde::de::DeserializeOwned;
-use std::cell::RefCell;
-use std::sync::Mutex;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
 use std::io::Read;
+use std::time::{Duration as StdDuration, Instant};
 
-const ENDPOINT: &'static str = "https://merchant-api.jet.com/api";
+/// A deserialized response body along with the HTTP status and headers,
+/// e.g. `Date` or rate-limit headers Jet sent
+#[derive(Debug)]
+pub struct ApiResponse<T> {
+  pub value: T,
+  pub status: StatusCode,
+  pub headers: HeaderMap,
+}
+
+impl<T> ApiResponse<T> {
+  /// Value of the header `name`, if present and valid text
+  pub fn header(&self, name: &str) -> Option<&str> {
+    self.headers.get(name).and_then(|value| value.to_str().ok())
+  }
+}
+
+/// A request as it would be sent to Jet, returned by the `preview_*`
+/// methods without sending anything. The bearer token is redacted.
+#[derive(Debug, Clone)]
+pub struct PreparedRequest {
+  pub method: Method,
+  pub url: String,
+  pub headers: HeaderMap,
+  pub body: Option<String>,
+}
+
+impl PreparedRequest {
+  pub(crate) fn new(method: Method, url: String, headers: HeaderMap, body: Option<&[u8]>) -> Self {
+    PreparedRequest {
+      method,
+      url,
+      headers,
+      body: body.map(|body| String::from_utf8_lossy(body).into_owned()),
+    }
+  }
+}
+
+/// Content type of a response, if sent and valid text
+pub(crate) fn content_type(headers: &HeaderMap) -> Option<&str> {
+  headers.get(CONTENT_TYPE).and_then(|value| value.to_str().ok())
+}
+
+/// Whether the response is an HTML page, e.g. Jet's maintenance page
+pub(crate) fn is_html(headers: &HeaderMap) -> bool {
+  content_type(headers).is_some_and(|content_type| content_type.trim_start().starts_with("text/html"))
+}
+
+/// Parses a successful response body with `json_or_empty`. An HTML body,
+/// e.g. a maintenance page served with a 200, is an `Error::Request`
+/// instead of a confusing JSON error.
+pub(crate) fn parse_body<T: DeserializeOwned>(path: &str, status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Result<T> {
+  if is_html(headers) {
+    return Err(Error::Request {
+      path: path.to_owned(),
+      status,
+      body: String::from_utf8_lossy(body).into_owned(),
+    });
+  }
+  json_or_empty(body)
+}
 
-#[derive(Debug, Deserialize)]
-pub(crate) struct Token {
-  id_token: String,
-  token_type: String,
-  expires_on: DateTime<Utc>,
+/// Parses a response body, treating an empty body like `null` so that a
+/// 204 or empty 200 works for `()`, `Option` and other types that accept it
+pub(crate) fn json_or_empty<T: DeserializeOwned>(body: &[u8]) -> Result<T> {
+  if body.iter().all(u8::is_ascii_whitespace) {
+    return Ok(serde_json::from_str("null")?);
+  }
+  Ok(serde_json::from_slice(body)?)
+}
+
+/// Stands in for the bearer token in a `PreparedRequest`
+pub(crate) const REDACTED_AUTHORIZATION: &str = "Bearer <redacted>";
+
+/// Production API base URL, [`HOST`] followed by [`BASE_PATH`]
+pub const ENDPOINT: &str = "https://merchant-api.jet.com/api";
+
+/// Sandbox API base URL, [`SANDBOX_HOST`] followed by [`BASE_PATH`].
+/// Sandbox API users and secrets only work here and orders are test orders.
+pub const SANDBOX_ENDPOINT: &str = "https://merchant-api-sandbox.jet.com/api";
+
+/// Production API host
+pub const HOST: &str = "https://merchant-api.jet.com";
+
+/// Sandbox API host
+pub const SANDBOX_HOST: &str = "https://merchant-api-sandbox.jet.com";
+
+/// Path of the API on the host, the default `ClientOptions::base_path`
+pub const BASE_PATH: &str = "/api";
+
+/// The Jet deployment a client talks to. Tokens are fetched from the same
+/// environment as the API calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Environment {
+  #[default]
+  Production,
+  Sandbox,
+}
+
+impl Environment {
+  /// API base URL, [`ENDPOINT`] or [`SANDBOX_ENDPOINT`]
+  pub fn endpoint(&self) -> &'static str {
+    match *self {
+      Environment::Production => ENDPOINT,
+      Environment::Sandbox => SANDBOX_ENDPOINT,
+    }
+  }
+
+  /// API host, [`HOST`] or [`SANDBOX_HOST`]
+  pub fn host(&self) -> &'static str {
+    match *self {
+      Environment::Production => HOST,
+      Environment::Sandbox => SANDBOX_HOST,
+    }
+  }
+}
+
+#[derive(Serialize)]
+pub(crate) struct TokenRequest<'a> {
+  pub user: &'a str,
+  pub pass: &'a str,
 }
 
 pub struct ClientOptions {
   pub api_user: String,
   pub secret: String,
+  /// Jet's ID for the merchant account, see `Client::merchant_id`. Not
+  /// sent with requests, since the token identifies the merchant.
   pub merchant_id: String,
+  /// Which Jet deployment to use. Defaults to `Environment::Production`.
+  pub environment: Environment,
+  /// Overrides the environment's host, without a trailing slash, e.g. for
+  /// a gateway. Requests go to `{host}{base_path}{path}`.
+  pub host: Option<String>,
+  /// Path of the API on the host. Defaults to [`BASE_PATH`], use an empty
+  /// string for a proxy that serves the API at its root.
+  pub base_path: String,
+  /// Overrides the whole API base URL, host and base path, without a
+  /// trailing slash, e.g. for a mock server. The token is fetched from
+  /// `{endpoint}/token`.
+  pub endpoint: Option<String>,
+  /// Number of times a request is retried after a transient failure
+  /// (429, 500, 502, 503, 504). Defaults to 0, no retries. When every
+  /// retry fails the error is `Error::ExhaustedRetries`.
+  pub max_retries: u32,
+  /// Upper bound of the delay before the first retry, doubled on each
+  /// further attempt. The delay before retry `n` (from 0) is random
+  /// between zero and `base_backoff * 2^n` ("full jitter"), so many
+  /// workers retrying together spread out. A `Retry-After` header sent by
+  /// Jet takes precedence.
+  pub base_backoff: StdDuration,
+  /// Stops retrying when the next delay would take longer than this since
+  /// the first attempt, returning the last error wrapped in
+  /// `Error::ExhaustedRetries` if at least one retry was made. Defaults to
+  /// `None`, bounded only by `max_retries`.
+  pub max_total_retry_duration: Option<StdDuration>,
+  /// Total timeout for each HTTP request made by a client built with
+  /// `Client::new`. A timed out request surfaces as `Error::Http`.
+  /// Ignored by `Client::with_http_client`.
+  pub timeout: Option<StdDuration>,
+  /// Where the auth token is cached. Defaults to an `InMemoryTokenStore`.
+  pub token_store: Arc<dyn TokenStore>,
+  /// `User-Agent` sent with every request instead of reqwest's default
+  pub user_agent: Option<String>,
+  /// Extra headers sent with every request
+  pub default_headers: HeaderMap,
+  /// Proxy URL all traffic of a client built with `Client::new` goes
+  /// through. An invalid URL surfaces as `Error::Http`.
+  pub proxy: Option<String>,
+  /// Maximum requests per second sent to the API, allowing bursts of up to
+  /// one second's worth. Must be positive, clients fail to build with
+  /// `Error::InvalidOptions` otherwise. Defaults to `None`, unlimited.
+  pub rate_limit: Option<f64>,
+  /// Whether a client built with `Client::new` sends `Accept-Encoding: gzip`
+  /// and decompresses gzipped responses. Defaults to `true`. Bulk upload
+  /// files are always gzipped, as Jet requires.
+  pub gzip: bool,
+  /// Jet has no idempotency keys. When set, an `acknowledge_order`,
+  /// `ship_order` or `cancel_order` identical to one that succeeded within
+  /// this window is skipped, so retrying after a lost response doesn't
+  /// submit it twice. An identical call made while the first is still in
+  /// flight is skipped too. Defaults to `None`, every call is sent.
+  /// `create_refund` is not covered, its response can't be replayed.
+  pub dedupe_window: Option<StdDuration>,
+  /// Most idle connections kept open to Jet by a client built with
+  /// `Client::new`. Defaults to `None`, reqwest's default.
+  pub pool_max_idle_per_host: Option<usize>,
+  /// How long an idle connection is kept open. Defaults to `None`,
+  /// reqwest's default.
+  pub pool_idle_timeout: Option<StdDuration>,
 }
 
+impl Default for ClientOptions {
+  fn default() -> Self {
+    ClientOptions {
+      api_user: String::new(),
+      secret: String::new(),
+      merchant_id: String::new(),
+      environment: Environment::Production,
+      host: None,
+      base_path: BASE_PATH.to_owned(),
+      endpoint: None,
+      max_retries: 0,
+      base_backoff: StdDuration::from_millis(500),
+      max_total_retry_duration: None,
+      timeout: None,
+      token_store: Arc::new(InMemoryTokenStore::new()),
+      user_agent: None,
+      default_headers: HeaderMap::new(),
+      proxy: None,
+      rate_limit: None,
+      gzip: true,
+      dedupe_window: None,
+      pool_max_idle_per_host: None,
+      pool_idle_timeout: None,
+    }
+  }
+}
+
+impl ClientOptions {
+  /// API base URL requests are sent to: `endpoint` if set, otherwise
+  /// `{host}{base_path}`
+  pub fn api_endpoint(&self) -> String {
+    match self.endpoint {
+      Some(ref endpoint) => endpoint.clone(),
+      None => format!(
+        "{}{}",
+        self.host.as_deref().unwrap_or_else(|| self.environment.host()),
+        self.base_path
+      ),
+    }
+  }
+
+  /// Options read from the environment:
+  ///
+  /// - `API_USER`, `SECRET`: required, `Error::InvalidCredentials` if unset
+  /// - `MERCHANT_ID`: optional
+  /// - `JET_ENDPOINT`: optional, see `endpoint`
+  /// - `JET_TIMEOUT_SECS`: optional, see `timeout`
+  ///
+  /// Everything else is left at its default.
+  pub fn from_env() -> Result<ClientOptions> {
+    Self::from_vars(|name| std::env::var(name).ok())
+  }
+
+  fn from_vars<F>(var: F) -> Result<ClientOptions>
+  where
+    F: Fn(&str) -> Option<String>,
+  {
+    let timeout = match var("JET_TIMEOUT_SECS") {
+      Some(value) => Some(
+        value
+          .trim()
+          .parse()
+          .map(StdDuration::from_secs)
+          .map_err(|_| Error::InvalidEnvVar { name: "JET_TIMEOUT_SECS", value })?,
+      ),
+      None => None,
+    };
+    let opts = ClientOptions {
+      api_user: var("API_USER").unwrap_or_default(),
+      secret: var("SECRET").unwrap_or_default(),
+      merchant_id: var("MERCHANT_ID").unwrap_or_default(),
+      endpoint: var("JET_ENDPOINT"),
+      timeout,
+      ..Default::default()
+    };
+    opts.check_credentials()?;
+    Ok(opts)
+  }
+
+  /// Fails if `api_user` or `secret` is blank, which Jet would only reject
+  /// with an unhelpful token error
+  pub(crate) fn check_credentials(&self) -> Result<()> {
+    if self.api_user.trim().is_empty() {
+      return Err(Error::InvalidCredentials { field: "api_user" });
+    }
+    if self.secret.trim().is_empty() {
+      return Err(Error::InvalidCredentials { field: "secret" });
+    }
+    Ok(())
+  }
+
+  /// Fails if `rate_limit` is zero, negative or not finite, which would
+  /// make the limiter compute an infinite wait
+  pub(crate) fn check_rate_limit(&self) -> Result<()> {
+    match self.rate_limit {
+      Some(rate) if !(rate.is_finite() && rate > 0.0) => Err(Error::InvalidOptions {
+        option: "rate_limit",
+        value: rate.to_string(),
+      }),
+      _ => Ok(()),
+    }
+  }
+
+  /// Headers added to every request, before authorization. `Accept` is
+  /// `application/json` unless `default_headers` sets it.
+  pub(crate) fn base_headers(&self) -> Result<HeaderMap> {
+    let mut map = self.default_headers.clone();
+    map.entry(ACCEPT).or_insert(HeaderValue::from_static("application/json"));
+    if let Some(ref user_agent) = self.user_agent {
+      map.insert(USER_AGENT, HeaderValue::from_str(user_agent)?);
+    }
+    Ok(map)
+  }
+}
+
+/// Cloning is cheap, clones share the token, rate limit and connection pool
+#[derive(Clone)]
 pub struct Client {
-  options: ClientOptions,
-  token: Mutex<RefCell<Option<Token>>>,
+  options: Arc<ClientOptions>,
+  /// Held while fetching a new token so only one thread refreshes at a time
+  refresh: Arc<Mutex<()>>,
+  limiter: Option<Arc<RateLimiter>>,
+  recent: Option<Arc<RecentRequests>>,
+  /// Fulfillment nodes cached by `fulfillment_nodes`
+  pub(crate) nodes: Arc<Mutex<Option<Arc<[FulfillmentNode]>>>>,
   client: reqwest::blocking::Client,
 }
 
 impl Client {
   pub fn new(opts: ClientOptions) -> Result<Client> {
+    opts.check_credentials()?;
+    opts.check_rate_limit()?;
+    let mut builder = reqwest::blocking::Client::builder().gzip(opts.gzip);
+    if let Some(timeout) = opts.timeout {
+      builder = builder.timeout(timeout);
+    }
+    if let Some(ref proxy) = opts.proxy {
+      builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+    if let Some(max_idle) = opts.pool_max_idle_per_host {
+      builder = builder.pool_max_idle_per_host(max_idle);
+    }
+    if let Some(idle_timeout) = opts.pool_idle_timeout {
+      builder = builder.pool_idle_timeout(idle_timeout);
+    }
     Ok(Client {
-      options: opts,
-      token: Mutex::new(RefCell::new(None)),
-      client: reqwest::blocking::Client::new(),
+      client: builder.build()?,
+      limiter: opts.rate_limit.map(|rate| Arc::new(RateLimiter::new(rate))),
+      recent: opts.dedupe_window.map(|window| Arc::new(RecentRequests::new(window))),
+      options: Arc::new(opts),
+      refresh: Arc::new(Mutex::new(())),
+      nodes: Arc::new(Mutex::new(None)),
     })
   }
 
-  pub fn with_http_client(opts: ClientOptions, http_client: reqwest::blocking::Client) -> Client {
-    Client {
-      options: opts,
-      token: Mutex::new(RefCell::new(None)),
+  /// Builds a client from `ClientOptions::from_env`
+  pub fn from_env() -> Result<Client> {
+    Client::new(ClientOptions::from_env()?)
+  }
+
+  pub fn with_http_client(opts: ClientOptions, http_client: reqwest::blocking::Client) -> Result<Client> {
+    opts.check_credentials()?;
+    opts.check_rate_limit()?;
+    Ok(Client {
+      limiter: opts.rate_limit.map(|rate| Arc::new(RateLimiter::new(rate))),
+      recent: opts.dedupe_window.map(|window| Arc::new(RecentRequests::new(window))),
+      options: Arc::new(opts),
+      refresh: Arc::new(Mutex::new(())),
+      nodes: Arc::new(Mutex::new(None)),
       client: http_client,
-    }
+    })
+  }
+
+  /// The underlying HTTP client, for requests outside the Jet API such as
+  /// bulk file uploads
+  pub(crate) fn http_client(&self) -> &reqwest::blocking::Client {
+    &self.client
+  }
+
+  /// The merchant ID this client was configured with. No endpoint
+  /// currently needs it, Jet identifies the merchant from the auth token.
+  pub fn merchant_id(&self) -> &str {
+    &self.options.merchant_id
+  }
+
+  /// When the cached token expires, or `None` if no token was fetched yet
+  pub fn token_expires_on(&self) -> Option<DateTime<Utc>> {
+    self.options.token_store.load().map(|token| token.expires_on)
+  }
+
+  /// Fetches a new token even if the cached one is still valid
+  pub fn refresh_token(&self) -> Result<()> {
+    let _refresh = self.refresh.lock().expect("lock token refresh");
+    let token = self.get_token()?;
+    self.options.token_store.save(&token);
+    Ok(())
   }
 
   pub(crate) fn with_token<T, F>(&self, f: F) -> Result<T>
   where
     F: FnOnce(&Token) -> Result<T>,
   {
-    let guard = self.token.lock().expect("lock token");
-    let token: &mut Option<Token> = &mut guard.borrow_mut();
-    match *token {
-      Some(ref token) if token.expires_on - Duration::minutes(15) >= Utc::now() => f(&token),
-      _ => {
-        token.replace(self.get_token()?);
-        f(&token.as_ref().unwrap())
-      }
+    let store = &self.options.token_store;
+    if let Some(token) = store.load().filter(Token::is_fresh) {
+      return f(&token);
     }
-  }
 
-  fn get_token(&self) -> Result<Token> {
-    #[derive(Serialize)]
-    pub struct TokenRequest<'a> {
-      pub user: &'a str,
-      pub pass: &'a str,
+    let _refresh = self.refresh.lock().expect("lock token refresh");
+
+    // another thread may have refreshed while we waited for the lock
+    if let Some(token) = store.load().filter(Token::is_fresh) {
+      return f(&token);
     }
 
+    let token = self.get_token()?;
+    store.save(&token);
+    f(&token)
+  }
+
+  fn get_token(&self) -> Result<Token> {
+    let started = Instant::now();
     let mut res = self
       .client
-      .post(&format!("{}/token", ENDPOINT))
+      .post(format!("{}/token", self.options.api_endpoint()))
+      .headers(self.options.base_headers()?)
       .json(&TokenRequest {
         user: &self.options.api_user,
         pass: &self.options.secret,
       })
-      .send()?;
+      .send()
+      .inspect_err(|err| {
+        trace::send_error(&Method::POST, "/token", err, started.elapsed());
+      })?;
+    trace::response(&Method::POST, "/token", res.status(), started.elapsed(), 0);
 
     if !res.status().is_success() {
       let mut body = String::new();
@@ -85,69 +450,160 @@ impl Client {
     res.json().map_err(Into::into)
   }
 
-  pub(crate) fn request<T, F>(&self, method: Method, path: &str, f: F) -> Result<T>
+  pub(crate) fn send<F>(&self, method: Method, path: &str, f: F) -> Result<Response>
   where
-    T: DeserializeOwned,
-    F: FnOnce(RequestBuilder) -> RequestBuilder,
+    F: Fn(RequestBuilder) -> RequestBuilder,
   {
     use headers::{HeaderMapExt, Authorization};
-    use reqwest::header::HeaderMap;
 
-    let mut req = self.with_token(|token| -> Result<RequestBuilder> {
-      let mut req = self
-        .client
-        .request(method, &format!("{}{}", ENDPOINT, path));
-      req = req.headers({
-        let mut map = HeaderMap::new();
-        map.typed_insert(Authorization::bearer(&token.id_token).map_err(|_| Error::InvalidBearerToken)?);
-        map
-      });
-      Ok(req)
-    })?;
+    let mut attempt = 0;
+    let mut refreshed = false;
+    let first_attempt = Instant::now();
+    loop {
+      let mut req = self.with_token(|token| -> Result<RequestBuilder> {
+        let mut req = self
+          .client
+          .request(method.clone(), format!("{}{}", self.options.api_endpoint(), path));
+        req = req.headers({
+          let mut map = self.options.base_headers()?;
+          map.typed_insert(Authorization::bearer(&token.id_token).map_err(|_| Error::InvalidBearerToken)?);
+          map
+        });
+        Ok(req)
+      })?;
+
+      req = f(req);
 
-    req = f(req);
+      if let Some(ref limiter) = self.limiter {
+        std::thread::sleep(limiter.reserve());
+      }
 
-    let mut res = req.send()?;
+      let started = Instant::now();
+      let mut res = req.send().inspect_err(|err| {
+        trace::send_error(&method, path, err, started.elapsed());
+      })?;
+      let status = res.status();
+      trace::response(&method, path, status, started.elapsed(), attempt);
 
-    if !res.status().is_success() {
+      if status.is_success() {
+        return Ok(res);
+      }
+
+      // The token may have expired since it was checked, refresh it and
+      // retry once
+      if status == StatusCode::UNAUTHORIZED && !refreshed {
+        refreshed = true;
+        self.refresh_token()?;
+        continue;
+      }
+
+      if attempt < self.options.max_retries && retry::is_transient(status) {
+        let delay = retry::delay(res.headers(), self.options.base_backoff, attempt);
+        if retry::within_budget(first_attempt.elapsed(), delay, self.options.max_total_retry_duration) {
+          attempt += 1;
+          std::thread::sleep(delay);
+          continue;
+        }
+      }
+
+      let content_type = content_type(res.headers()).map(ToOwned::to_owned);
       let mut body = String::new();
       res.read_to_string(&mut body)?;
-      return Err(Error::Request { path: path.to_owned(), status: res.status(), body });
+      let err = Error::from_response(path, status, content_type.as_deref(), body);
+      if attempt > 0 {
+        return Err(Error::ExhaustedRetries { attempts: attempt + 1, last: Box::new(err) });
+      }
+      return Err(err);
     }
-
-    res.json().map_err(Into::into)
   }
 
-  pub(crate) fn request_no_content<F>(&self, method: Method, path: &str, f: F) -> Result<()>
+  /// Builds the request `send` would make, without a token or sending it
+  pub(crate) fn prepare<F>(&self, method: Method, path: &str, f: F) -> Result<PreparedRequest>
   where
-    F: FnOnce(RequestBuilder) -> RequestBuilder,
+    F: Fn(RequestBuilder) -> RequestBuilder,
   {
-    use headers::{HeaderMapExt, Authorization};
-    use reqwest::header::HeaderMap;
+    let mut headers = self.options.base_headers()?;
+    headers.insert(AUTHORIZATION, HeaderValue::from_static(REDACTED_AUTHORIZATION));
+    let req = f(self
+      .client
+      .request(method, format!("{}{}", self.options.api_endpoint(), path))
+      .headers(headers))
+    .build()?;
+    Ok(PreparedRequest::new(
+      req.method().clone(),
+      req.url().to_string(),
+      req.headers().clone(),
+      req.body().and_then(|body| body.as_bytes()),
+    ))
+  }
 
-    let mut req = self.with_token(|token| -> Result<RequestBuilder> {
-      let mut req = self
-        .client
-        .request(method, &format!("{}{}", ENDPOINT, path));
-      req = req.headers({
-        let mut map = HeaderMap::new();
-        map.typed_insert(Authorization::bearer(&token.id_token).map_err(|_| Error::InvalidBearerToken)?);
-        map
-      });
-      Ok(req)
-    })?;
+  pub(crate) fn request<T, F>(&self, method: Method, path: &str, f: F) -> Result<T>
+  where
+    T: DeserializeOwned,
+    F: Fn(RequestBuilder) -> RequestBuilder,
+  {
+    let res = self.send(method, path, f)?;
+    let status = res.status();
+    let headers = res.headers().clone();
+    parse_body(path, status, &headers, &res.bytes()?)
+  }
 
-    req = f(req);
+  pub(crate) fn request_full<T, F>(&self, method: Method, path: &str, f: F) -> Result<ApiResponse<T>>
+  where
+    T: DeserializeOwned,
+    F: Fn(RequestBuilder) -> RequestBuilder,
+  {
+    let res = self.send(method, path, f)?;
+    let status = res.status();
+    let headers = res.headers().clone();
+    Ok(ApiResponse {
+      value: parse_body(path, status, &headers, &res.bytes()?)?,
+      status,
+      headers,
+    })
+  }
 
-    let mut res = req.send()?;
+  pub(crate) fn request_no_content<F>(&self, method: Method, path: &str, f: F) -> Result<()>
+  where
+    F: Fn(RequestBuilder) -> RequestBuilder,
+  {
+    self.send(method, path, f)?;
+    Ok(())
+  }
 
-    if !res.status().is_success() {
-      let mut body = String::new();
-      res.read_to_string(&mut body)?;
-      return Err(Error::Request{ path: path.to_owned(), status: res.status(), body });
+  /// Sends `body` as JSON unless the same request succeeded within
+  /// `ClientOptions::dedupe_window`
+  pub(crate) fn request_deduped<B>(&self, method: Method, path: &str, body: &B) -> Result<()>
+  where
+    B: Serialize,
+  {
+    let key = match self.recent {
+      Some(ref recent) => {
+        let key = request_key(method.as_str(), path, &serde_json::to_string(body)?);
+        if !recent.reserve(&key) {
+          return Ok(());
+        }
+        Some(key)
+      }
+      None => None,
+    };
+    let res = self.request_no_content(method, path, |req| req.json(body));
+    if let (Some(recent), Some(key)) = (self.recent.as_ref(), key) {
+      match res {
+        Ok(()) => recent.complete(key),
+        Err(_) => recent.release(&key),
+      }
     }
+    res
+  }
 
-    Ok(())
+  /// Like `request_no_content` but returns the response headers
+  pub(crate) fn request_headers<F>(&self, method: Method, path: &str, f: F) -> Result<HeaderMap>
+  where
+    F: Fn(RequestBuilder) -> RequestBuilder,
+  {
+    let res = self.send(method, path, f)?;
+    Ok(res.headers().clone())
   }
 }
 
@@ -161,13 +617,448 @@ pub(crate) fn get_test_client() -> Client {
     api_user: env::var("API_USER").unwrap(),
     secret: env::var("SECRET").unwrap(),
     merchant_id: env::var("MERCHANT_ID").unwrap(),
+    endpoint: env::var("JET_ENDPOINT").ok(),
+    ..Default::default()
   }).unwrap()
 }
 
+#[cfg(test)]
+pub(crate) fn get_mock_client(server: &mockito::Server) -> Client {
+  Client::new(ClientOptions {
+    api_user: "mock-user".to_owned(),
+    secret: "mock-secret".to_owned(),
+    endpoint: Some(server.url()),
+    ..Default::default()
+  }).unwrap()
+}
+
+#[cfg(test)]
+pub(crate) const MOCK_TOKEN: &str =
+  r#"{"id_token":"mock-token","token_type":"Bearer","expires_on":"2099-01-01T00:00:00Z"}"#;
+
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  #[test]
+  fn test_with_token_concurrent_refresh() {
+    let mut server = mockito::Server::new();
+    let token_mock = server
+      .mock("POST", "/token")
+      .with_body(MOCK_TOKEN)
+      .expect(1)
+      .create();
+
+    let client = get_mock_client(&server);
+    std::thread::scope(|scope| {
+      for _ in 0..8 {
+        scope.spawn(|| {
+          client
+            .with_token(|token| -> Result<()> {
+              assert_eq!(token.id_token, "mock-token");
+              Ok(())
+            })
+            .unwrap();
+        });
+      }
+    });
+
+    token_mock.assert();
+  }
+
+  #[test]
+  fn test_user_agent_and_default_headers() {
+    let mut server = mockito::Server::new();
+    let token_mock = server
+      .mock("POST", "/token")
+      .match_header("user-agent", "jet-test/1.0")
+      .with_body(MOCK_TOKEN)
+      .create();
+    let orders_mock = server
+      .mock("GET", "/orders/ready")
+      .match_header("user-agent", "jet-test/1.0")
+      .match_header("x-integrator", "ventmere")
+      .with_body(r#"{"order_urls":[]}"#)
+      .create();
+
+    let client = Client::new(ClientOptions {
+      api_user: "mock-user".to_owned(),
+      secret: "mock-secret".to_owned(),
+      endpoint: Some(server.url()),
+      user_agent: Some("jet-test/1.0".to_owned()),
+      default_headers: {
+        let mut map = HeaderMap::new();
+        map.insert("x-integrator", HeaderValue::from_static("ventmere"));
+        map
+      },
+      ..Default::default()
+    }).unwrap();
+    client.get_orders(crate::orders::OrderStatus::Ready).unwrap();
+
+    token_mock.assert();
+    orders_mock.assert();
+  }
+
+  #[test]
+  fn test_invalid_proxy() {
+    let res = Client::new(ClientOptions {
+      api_user: "mock-user".to_owned(),
+      secret: "mock-secret".to_owned(),
+      proxy: Some("not a proxy url".to_owned()),
+      ..Default::default()
+    });
+    assert!(matches!(res, Err(Error::Http(_))));
+  }
+
+  #[test]
+  fn test_refresh_token() {
+    let mut server = mockito::Server::new();
+    let token_mock = server
+      .mock("POST", "/token")
+      .with_body(MOCK_TOKEN)
+      .expect(2)
+      .create();
+
+    let client = get_mock_client(&server);
+    assert_eq!(client.token_expires_on(), None);
+    client.refresh_token().unwrap();
+    client.refresh_token().unwrap();
+    assert_eq!(
+      client.token_expires_on().unwrap().to_rfc3339(),
+      "2099-01-01T00:00:00+00:00"
+    );
+    token_mock.assert();
+  }
+
+  #[test]
+  fn test_unauthorized_refreshes_token_once() {
+    let mut server = mockito::Server::new();
+    let token_mock = server
+      .mock("POST", "/token")
+      .with_body(MOCK_TOKEN)
+      .expect(2)
+      .create();
+    let unauthorized_mock = server
+      .mock("GET", "/orders/ready")
+      .with_status(401)
+      .expect(1)
+      .create();
+    // mockito prefers the 401 mock until its expected hit count is reached
+    server
+      .mock("GET", "/orders/ready")
+      .with_body(r#"{"order_urls":[]}"#)
+      .create();
+
+    let client = get_mock_client(&server);
+    let res: serde_json::Value = client.request(Method::GET, "/orders/ready", std::convert::identity).unwrap();
+    assert_eq!(res["order_urls"], serde_json::json!([]));
+    unauthorized_mock.assert();
+    token_mock.assert();
+
+    let mut server = mockito::Server::new();
+    server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+    let unauthorized_mock = server
+      .mock("GET", "/orders/ready")
+      .with_status(401)
+      .expect(2)
+      .create();
+    let err = get_mock_client(&server)
+      .request_no_content(Method::GET, "/orders/ready", std::convert::identity)
+      .unwrap_err();
+    assert_eq!(err.status_code(), Some(StatusCode::UNAUTHORIZED));
+    unauthorized_mock.assert();
+  }
+
+  #[test]
+  fn test_gzip() {
+    use crate::bulk::gzip;
+
+    let mut server = mockito::Server::new();
+    server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+    let gzip_mock = server
+      .mock("GET", "/orders/ready")
+      .match_header("accept-encoding", mockito::Matcher::Regex("gzip".to_owned()))
+      .with_header("content-encoding", "gzip")
+      .with_body(gzip(br#"{"order_urls":["/orders/withoutShipmentDetail/aa01"]}"#).unwrap())
+      .create();
+
+    let res: serde_json::Value = get_mock_client(&server)
+      .request(Method::GET, "/orders/ready", std::convert::identity)
+      .unwrap();
+    assert_eq!(res["order_urls"][0], "/orders/withoutShipmentDetail/aa01");
+    gzip_mock.assert();
+
+    let plain_mock = server
+      .mock("GET", "/orders/complete")
+      .match_header("accept-encoding", mockito::Matcher::Missing)
+      .with_body(r#"{"order_urls":[]}"#)
+      .create();
+    let client = Client::new(ClientOptions {
+      api_user: "mock-user".to_owned(),
+      secret: "mock-secret".to_owned(),
+      endpoint: Some(server.url()),
+      gzip: false,
+      ..Default::default()
+    })
+    .unwrap();
+    client
+      .request_no_content(Method::GET, "/orders/complete", std::convert::identity)
+      .unwrap();
+    plain_mock.assert();
+  }
+
+  #[test]
+  fn test_clones_share_token() {
+    let mut server = mockito::Server::new();
+    let token_mock = server
+      .mock("POST", "/token")
+      .with_body(MOCK_TOKEN)
+      .expect(1)
+      .create();
+
+    let client = get_mock_client(&server);
+    let handles: Vec<_> = (0..4)
+      .map(|_| {
+        let client = client.clone();
+        std::thread::spawn(move || client.with_token(|token| Ok(token.id_token.clone())).unwrap())
+      })
+      .collect();
+    for handle in handles {
+      assert_eq!(handle.join().unwrap(), "mock-token");
+    }
+    assert!(client.token_expires_on().is_some());
+    token_mock.assert();
+  }
+
+  #[test]
+  fn test_environment_endpoint() {
+    let options = ClientOptions::default();
+    assert_eq!(options.api_endpoint(), ENDPOINT);
+
+    let options = ClientOptions {
+      environment: Environment::Sandbox,
+      ..Default::default()
+    };
+    assert_eq!(options.api_endpoint(), SANDBOX_ENDPOINT);
+
+    let options = ClientOptions {
+      environment: Environment::Sandbox,
+      endpoint: Some("http://localhost:8080".to_owned()),
+      ..Default::default()
+    };
+    assert_eq!(options.api_endpoint(), "http://localhost:8080");
+
+    let options = ClientOptions {
+      host: Some("https://jet-gateway.example.com".to_owned()),
+      ..Default::default()
+    };
+    assert_eq!(options.api_endpoint(), "https://jet-gateway.example.com/api");
+
+    let options = ClientOptions {
+      environment: Environment::Sandbox,
+      base_path: "/merchant/v2".to_owned(),
+      ..Default::default()
+    };
+    assert_eq!(options.api_endpoint(), "https://merchant-api-sandbox.jet.com/merchant/v2");
+  }
+
+  #[test]
+  fn test_empty_credentials() {
+    let res = Client::new(ClientOptions {
+      secret: "secret".to_owned(),
+      ..Default::default()
+    });
+    assert!(matches!(res, Err(Error::InvalidCredentials { field: "api_user" })));
+
+    let res = Client::new(ClientOptions {
+      api_user: "user".to_owned(),
+      secret: " ".to_owned(),
+      ..Default::default()
+    });
+    assert!(matches!(res, Err(Error::InvalidCredentials { field: "secret" })));
+
+    let res = Client::with_http_client(ClientOptions::default(), reqwest::blocking::Client::new());
+    assert!(matches!(res, Err(Error::InvalidCredentials { field: "api_user" })));
+  }
+
+  #[test]
+  fn test_invalid_rate_limit() {
+    for rate in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+      let opts = || ClientOptions {
+        api_user: "user".to_owned(),
+        secret: "secret".to_owned(),
+        rate_limit: Some(rate),
+        ..Default::default()
+      };
+      let res = Client::new(opts());
+      assert!(matches!(res, Err(Error::InvalidOptions { option: "rate_limit", .. })), "{}", rate);
+      let res = Client::with_http_client(opts(), reqwest::blocking::Client::new());
+      assert!(matches!(res, Err(Error::InvalidOptions { option: "rate_limit", .. })), "{}", rate);
+    }
+  }
+
+  #[test]
+  fn test_request_empty_body() {
+    let mut server = mockito::Server::new();
+    server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+    server.mock("PUT", "/orders/empty").with_status(204).create();
+    server.mock("PUT", "/orders/blank").with_body(" \n").create();
+
+    let client = get_mock_client(&server);
+    let res: () = client.request(Method::PUT, "/orders/empty", std::convert::identity).unwrap();
+    assert_eq!(res, ());
+    let res: Option<serde_json::Value> = client.request(Method::PUT, "/orders/blank", std::convert::identity).unwrap();
+    assert_eq!(res, None);
+    let res: Result<crate::orders::GetOrdersResponse> = client.request(Method::PUT, "/orders/empty", std::convert::identity);
+    assert!(matches!(res, Err(Error::Json(_))));
+  }
+
+  #[test]
+  fn test_exhausted_retries() {
+    let mut server = mockito::Server::new();
+    server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+    let orders_mock = server
+      .mock("GET", "/orders/ready")
+      .with_status(503)
+      .expect(3)
+      .create();
+
+    let client = Client::new(ClientOptions {
+      api_user: "mock-user".to_owned(),
+      secret: "mock-secret".to_owned(),
+      endpoint: Some(server.url()),
+      max_retries: 2,
+      base_backoff: StdDuration::from_millis(1),
+      ..Default::default()
+    }).unwrap();
+    match client.request_no_content(Method::GET, "/orders/ready", std::convert::identity) {
+      Err(Error::ExhaustedRetries { attempts: 3, ref last }) => {
+        assert_eq!(last.status_code(), Some(StatusCode::SERVICE_UNAVAILABLE));
+      }
+      other => panic!("unexpected result: {:?}", other),
+    }
+    orders_mock.assert();
+  }
+
+  #[test]
+  fn test_retry_budget() {
+    let mut server = mockito::Server::new();
+    server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+    let retried_mock = server
+      .mock("GET", "/orders/ready")
+      .with_status(503)
+      .with_header("retry-after", "0")
+      .expect(1)
+      .create();
+    // mockito prefers the first mock until its expected hit count is reached
+    let over_budget_mock = server
+      .mock("GET", "/orders/ready")
+      .with_status(503)
+      .with_header("retry-after", "10")
+      .expect(2)
+      .create();
+
+    let client = Client::new(ClientOptions {
+      api_user: "mock-user".to_owned(),
+      secret: "mock-secret".to_owned(),
+      endpoint: Some(server.url()),
+      max_retries: 5,
+      max_total_retry_duration: Some(StdDuration::from_secs(1)),
+      ..Default::default()
+    }).unwrap();
+    match client.request_no_content(Method::GET, "/orders/ready", std::convert::identity) {
+      Err(Error::ExhaustedRetries { attempts: 2, ref last }) => {
+        assert_eq!(last.status_code(), Some(StatusCode::SERVICE_UNAVAILABLE));
+      }
+      other => panic!("unexpected result: {:?}", other),
+    }
+    retried_mock.assert();
+
+    // the budget stops the first retry, so the error is returned as is
+    let err = client
+      .request_no_content(Method::GET, "/orders/ready", std::convert::identity)
+      .unwrap_err();
+    assert!(matches!(err, Error::Request { .. }), "{:?}", err);
+    over_budget_mock.assert();
+  }
+
+  #[test]
+  fn test_options_from_vars() {
+    fn vars(pairs: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+      move |name| pairs.iter().find(|(key, _)| *key == name).map(|(_, value)| value.to_string())
+    }
+
+    let opts = ClientOptions::from_vars(vars(&[
+      ("API_USER", "user"),
+      ("SECRET", "secret"),
+      ("JET_ENDPOINT", "http://127.0.0.1:1"),
+      ("JET_TIMEOUT_SECS", "30"),
+    ])).unwrap();
+    assert_eq!(opts.api_user, "user");
+    assert_eq!(opts.merchant_id, "");
+    assert_eq!(opts.api_endpoint(), "http://127.0.0.1:1");
+    assert_eq!(opts.timeout, Some(StdDuration::from_secs(30)));
+
+    let res = ClientOptions::from_vars(vars(&[("API_USER", "user")]));
+    assert!(matches!(res, Err(Error::InvalidCredentials { field: "secret" })));
+
+    let res = ClientOptions::from_vars(vars(&[("API_USER", "user"), ("SECRET", "secret"), ("JET_TIMEOUT_SECS", "soon")]));
+    assert!(matches!(res, Err(Error::InvalidEnvVar { name: "JET_TIMEOUT_SECS", .. })));
+  }
+
+  #[test]
+  fn test_accept_json() {
+    let mut server = mockito::Server::new();
+    let token_mock = server
+      .mock("POST", "/token")
+      .match_header("accept", "application/json")
+      .with_body(MOCK_TOKEN)
+      .create();
+    let orders_mock = server
+      .mock("GET", "/orders/ready")
+      .match_header("accept", "application/json")
+      .with_body(r#"{"order_urls":[]}"#)
+      .create();
+
+    get_mock_client(&server).get_orders(crate::orders::OrderStatus::Ready).unwrap();
+    token_mock.assert();
+    orders_mock.assert();
+  }
+
+  #[test]
+  fn test_html_responses() {
+    let mut server = mockito::Server::new();
+    server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+    let maintenance = "<html><body><h1>Down for maintenance</h1></body></html>";
+    server
+      .mock("GET", "/orders/ready")
+      .with_status(503)
+      .with_header("content-type", "text/html")
+      .with_body(maintenance)
+      .create();
+    server
+      .mock("GET", "/orders/complete")
+      .with_header("content-type", "text/html; charset=utf-8")
+      .with_body(maintenance)
+      .create();
+
+    let client = get_mock_client(&server);
+    match client.get_orders(crate::orders::OrderStatus::Ready) {
+      Err(Error::Request { status, ref body, .. }) => {
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body, maintenance);
+      }
+      other => panic!("unexpected result: {:?}", other),
+    }
+    match client.get_orders(crate::orders::OrderStatus::Complete) {
+      Err(Error::Request { status, ref body, .. }) => {
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, maintenance);
+      }
+      other => panic!("unexpected result: {:?}", other),
+    }
+  }
+
   #[test]
   fn test_with_token() {
     let client = get_test_client();
@@ -0,0 +1,84 @@
+//! Client-side deduplication of mutating requests
+//!
+//! Jet has no idempotency key header, so a retried acknowledgement or
+//! shipment would be submitted twice. Requests that succeeded are remembered
+//! for a while and identical repeats are skipped.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub(crate) struct RecentRequests {
+  window: Duration,
+  sent: Mutex<HashMap<String, Instant>>,
+}
+
+impl RecentRequests {
+  pub(crate) fn new(window: Duration) -> RecentRequests {
+    RecentRequests {
+      window,
+      sent: Mutex::new(HashMap::new()),
+    }
+  }
+
+  /// Claims `key` for a request about to be sent. Returns false if the
+  /// same request succeeded within the window or is still in flight, it
+  /// should be skipped then. Checking and claiming under one lock keeps
+  /// concurrent identical calls from both being sent.
+  pub(crate) fn reserve(&self, key: &str) -> bool {
+    let mut sent = self.sent.lock().expect("lock recent requests");
+    let window = self.window;
+    sent.retain(|_, at| at.elapsed() < window);
+    if sent.contains_key(key) {
+      return false;
+    }
+    sent.insert(key.to_owned(), Instant::now());
+    true
+  }
+
+  /// Records that the reserved request succeeded, the window starts now
+  pub(crate) fn complete(&self, key: String) {
+    self.sent.lock().expect("lock recent requests").insert(key, Instant::now());
+  }
+
+  /// Drops a reservation whose request failed, so it can be retried
+  pub(crate) fn release(&self, key: &str) {
+    self.sent.lock().expect("lock recent requests").remove(key);
+  }
+}
+
+/// Identifies a request by its method, path and JSON body
+pub(crate) fn request_key(method: &str, path: &str, body: &str) -> String {
+  format!("{} {} {}", method, path, body)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_recent_requests() {
+    let recent = RecentRequests::new(Duration::from_millis(50));
+    let key = request_key("PUT", "/orders/aa01/shipped", "{}");
+    assert!(recent.reserve(&key));
+    assert!(!recent.reserve(&key));
+    recent.complete(key.clone());
+    assert!(!recent.reserve(&key));
+    assert!(recent.reserve(&request_key("PUT", "/orders/aa02/shipped", "{}")));
+    std::thread::sleep(Duration::from_millis(60));
+    assert!(recent.reserve(&key));
+    recent.release(&key);
+    assert!(recent.reserve(&key));
+  }
+
+  #[test]
+  fn test_concurrent_reserve() {
+    let recent = RecentRequests::new(Duration::from_secs(60));
+    let key = request_key("PUT", "/orders/aa01/shipped", "{}");
+    let reserved = std::thread::scope(|scope| {
+      let handles: Vec<_> = (0..8).map(|_| scope.spawn(|| recent.reserve(&key))).collect();
+      handles.into_iter().map(|handle| handle.join().unwrap()).filter(|&ok| ok).count()
+    });
+    assert_eq!(reserved, 1);
+  }
+}
@@ -14,14 +14,266 @@ pub enum Error {
     status: StatusCode, 
     body: String
   },
+  #[error("not found: path = '{path}'")]
+  NotFound {
+    path: String,
+  },
+  #[error("jet api error: path = '{path}', status = '{status}', errors = '{}'", errors.join("; "))]
+  JetApi {
+    path: String,
+    status: StatusCode,
+    errors: Vec<String>,
+    error_id: Option<String>,
+  },
   #[error("invalid bearer token")]
   InvalidBearerToken,
+  #[error("invalid credentials: '{field}' is empty")]
+  InvalidCredentials {
+    field: &'static str,
+  },
+  #[error("invalid option {option}: '{value}'")]
+  InvalidOptions {
+    option: &'static str,
+    value: String,
+  },
+  #[error("invalid environment variable {name}: '{value}'")]
+  InvalidEnvVar {
+    name: &'static str,
+    value: String,
+  },
+  #[error("validation: {0}")]
+  Validation(#[from] ValidationError),
+  #[error("invalid header value: {0}")]
+  InvalidHeader(#[from] reqwest::header::InvalidHeaderValue),
   #[error("json: {0}")]
   Json(#[from] serde_json::Error),
   #[error("http: {0}")]
   Http(#[from] reqwest::Error),
   #[error("io: {0}")]
   Io(#[from] std::io::Error),
+  #[error("gave up after {attempts} attempts: {last}")]
+  ExhaustedRetries {
+    attempts: u32,
+    last: Box<Error>,
+  },
+  #[error("fulfill order: {step} failed: {source}")]
+  Fulfill {
+    step: FulfillStep,
+    source: Box<Error>,
+  },
+}
+
+/// The step of `Client::fulfill_order` that failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FulfillStep {
+  Acknowledge,
+  Ship,
+}
+
+impl std::fmt::Display for FulfillStep {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    f.write_str(match *self {
+      FulfillStep::Acknowledge => "acknowledge",
+      FulfillStep::Ship => "ship",
+    })
+  }
+}
+
+/// A request body rejected client-side before it is sent to Jet
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ValidationError {
+  #[error("no shipments")]
+  NoShipments,
+  #[error("shipment {shipment} has no items")]
+  NoShipmentItems { shipment: usize },
+  #[error("shipment {shipment} has an empty carrier")]
+  EmptyCarrier { shipment: usize },
+  #[error("shipment {shipment} has a negative quantity for sku '{merchant_sku}'")]
+  NegativeQuantity { shipment: usize, merchant_sku: String },
+  #[error("sku '{merchant_sku}' is not in the order")]
+  UnknownSku { merchant_sku: String },
+  #[error("orders can't be listed by unknown status '{status}'")]
+  UnknownOrderStatus { status: String },
+}
+
+/// Returned when parsing an unrecognized order status
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("unknown order status: '{0}'")]
+pub struct ParseOrderStatusError(pub String);
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+  /// The HTTP status Jet responded with, if the error came from a response
+  pub fn status_code(&self) -> Option<StatusCode> {
+    match *self {
+      Error::GetTokenRequest { status, .. }
+      | Error::Request { status, .. }
+      | Error::JetApi { status, .. } => Some(status),
+      Error::NotFound { .. } => Some(StatusCode::NOT_FOUND),
+      Error::Http(ref err) => err.status(),
+      Error::Fulfill { ref source, .. } => source.status_code(),
+      Error::ExhaustedRetries { ref last, .. } => last.status_code(),
+      _ => None,
+    }
+  }
+
+  /// True for throttling (429), server errors (5xx) and connection or
+  /// timeout failures, which are likely to succeed if retried
+  pub fn is_retryable(&self) -> bool {
+    if let Error::Http(ref err) = *self {
+      if err.is_connect() || err.is_timeout() {
+        return true;
+      }
+    }
+    match self.status_code() {
+      Some(status) => status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error(),
+      None => false,
+    }
+  }
+
+  /// True when Jet rejected the credentials or token (401, 403)
+  pub fn is_auth_error(&self) -> bool {
+    matches!(
+      self.status_code(),
+      Some(StatusCode::UNAUTHORIZED) | Some(StatusCode::FORBIDDEN)
+    )
+  }
+
+  /// Builds the error for a failed API request: `NotFound` for 404s,
+  /// otherwise Jet's structured `{ "errors": [...], "id": ... }` body when
+  /// it parses. A body with a non-JSON `content_type`, such as the HTML
+  /// page Jet serves during maintenance, is kept as-is in `Error::Request`.
+  pub(crate) fn from_response(path: &str, status: StatusCode, content_type: Option<&str>, body: String) -> Error {
+    if status == StatusCode::NOT_FOUND {
+      return Error::NotFound { path: path.to_owned() };
+    }
+    if content_type.is_some_and(|content_type| !is_json_content_type(content_type)) {
+      return Error::Request { path: path.to_owned(), status, body };
+    }
+
+    #[derive(Deserialize)]
+    struct ErrorBody {
+      errors: Vec<String>,
+      id: Option<String>,
+    }
+
+    match serde_json::from_str::<ErrorBody>(&body) {
+      Ok(parsed) => Error::JetApi {
+        path: path.to_owned(),
+        status,
+        errors: parsed.errors,
+        error_id: parsed.id,
+      },
+      Err(_) => Error::Request { path: path.to_owned(), status, body },
+    }
+  }
+}
+
+/// True for `application/json` and `+json` types, with or without parameters
+pub(crate) fn is_json_content_type(content_type: &str) -> bool {
+  let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+  mime == "application/json" || mime.ends_with("+json")
 }
 
-pub type Result<T, E = Error> = std::result::Result<T, E>;
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_classification() {
+    let err = Error::GetTokenRequest { status: StatusCode::UNAUTHORIZED, body: String::new() };
+    assert_eq!(err.status_code(), Some(StatusCode::UNAUTHORIZED));
+    assert!(err.is_auth_error());
+    assert!(!err.is_retryable());
+
+    let err = Error::Request { path: "/orders/ready".to_owned(), status: StatusCode::TOO_MANY_REQUESTS, body: String::new() };
+    assert_eq!(err.status_code(), Some(StatusCode::TOO_MANY_REQUESTS));
+    assert!(err.is_retryable());
+    assert!(!err.is_auth_error());
+
+    let err = Error::JetApi {
+      path: "/orders/ready".to_owned(),
+      status: StatusCode::SERVICE_UNAVAILABLE,
+      errors: vec![],
+      error_id: None,
+    };
+    assert!(err.is_retryable());
+
+    let err = Error::Request { path: "/orders/ready".to_owned(), status: StatusCode::FORBIDDEN, body: String::new() };
+    assert!(err.is_auth_error());
+    assert!(!err.is_retryable());
+
+    let err = Error::ExhaustedRetries {
+      attempts: 3,
+      last: Box::new(Error::from_response("/orders/ready", StatusCode::SERVICE_UNAVAILABLE, None, String::new())),
+    };
+    assert_eq!(err.status_code(), Some(StatusCode::SERVICE_UNAVAILABLE));
+    assert!(err.is_retryable());
+
+    let err = Error::InvalidBearerToken;
+    assert_eq!(err.status_code(), None);
+    assert!(!err.is_retryable());
+    assert!(!err.is_auth_error());
+
+    let err: Error = serde_json::from_str::<u32>("x").unwrap_err().into();
+    assert!(!err.is_retryable());
+
+    let err: Error = std::io::Error::other("io").into();
+    assert!(!err.is_retryable());
+
+    // nothing listens on port 1, so this fails to connect
+    let err: Error = reqwest::blocking::get("http://127.0.0.1:1").unwrap_err().into();
+    assert!(err.is_retryable());
+    assert!(!err.is_auth_error());
+  }
+
+  #[test]
+  fn test_from_response() {
+    let err = Error::from_response(
+      "/orders/ready",
+      StatusCode::BAD_REQUEST,
+      None,
+      r#"{"errors":["Invalid status","Missing field"],"id":"abc123"}"#.to_owned(),
+    );
+    match err {
+      Error::JetApi { ref errors, ref error_id, .. } => {
+        assert_eq!(errors, &["Invalid status", "Missing field"]);
+        assert_eq!(error_id.as_deref(), Some("abc123"));
+      }
+      ref other => panic!("unexpected error: {:?}", other),
+    }
+    assert_eq!(
+      err.to_string(),
+      "jet api error: path = '/orders/ready', status = '400 Bad Request', errors = 'Invalid status; Missing field'"
+    );
+
+    let err = Error::from_response("/orders/withoutShipmentDetail/aa01", StatusCode::NOT_FOUND, None, String::new());
+    match err {
+      Error::NotFound { ref path } => assert_eq!(path, "/orders/withoutShipmentDetail/aa01"),
+      ref other => panic!("unexpected error: {:?}", other),
+    }
+    assert_eq!(err.status_code(), Some(StatusCode::NOT_FOUND));
+
+    let err = Error::from_response("/orders/ready", StatusCode::BAD_GATEWAY, None, "Bad Gateway".to_owned());
+    match err {
+      Error::Request { body, .. } => assert_eq!(body, "Bad Gateway"),
+      other => panic!("unexpected error: {:?}", other),
+    }
+
+    let err = Error::from_response(
+      "/orders/ready",
+      StatusCode::BAD_REQUEST,
+      Some("text/plain"),
+      r#"{"errors":["Invalid status"]}"#.to_owned(),
+    );
+    assert!(matches!(err, Error::Request { .. }));
+    let err = Error::from_response(
+      "/orders/ready",
+      StatusCode::BAD_REQUEST,
+      Some("application/json; charset=utf-8"),
+      r#"{"errors":["Invalid status"]}"#.to_owned(),
+    );
+    assert!(matches!(err, Error::JetApi { .. }));
+  }
+}
@@ -0,0 +1,107 @@
+//! Typed IDs of orders, order items, SKUs and fulfillment nodes, so one
+//! can't be passed where another is expected
+//!
+//! Each serializes as a plain string and converts from `String` and `&str`.
+
+use std::borrow::Borrow;
+use std::fmt;
+
+macro_rules! id_type {
+  ($(#[$attr:meta])* $name:ident) => {
+    $(#[$attr])*
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+    #[serde(transparent)]
+    pub struct $name(pub String);
+
+    impl $name {
+      pub fn as_str(&self) -> &str {
+        &self.0
+      }
+    }
+
+    impl fmt::Display for $name {
+      fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+      }
+    }
+
+    impl AsRef<str> for $name {
+      fn as_ref(&self) -> &str {
+        &self.0
+      }
+    }
+
+    /// Allows looking up maps keyed by the ID with a `&str`
+    impl Borrow<str> for $name {
+      fn borrow(&self) -> &str {
+        &self.0
+      }
+    }
+
+    impl From<String> for $name {
+      fn from(value: String) -> Self {
+        $name(value)
+      }
+    }
+
+    impl<'a> From<&'a str> for $name {
+      fn from(value: &'a str) -> Self {
+        $name(value.to_owned())
+      }
+    }
+
+    impl From<$name> for String {
+      fn from(value: $name) -> Self {
+        value.0
+      }
+    }
+
+    impl PartialEq<str> for $name {
+      fn eq(&self, other: &str) -> bool {
+        self.0 == other
+      }
+    }
+
+    impl<'a> PartialEq<&'a str> for $name {
+      fn eq(&self, other: &&'a str) -> bool {
+        self.0 == *other
+      }
+    }
+  };
+}
+
+id_type!(
+  /// Jet's ID of an order, the `merchant_order_id`
+  MerchantOrderId
+);
+
+id_type!(
+  /// Jet's ID of an item within an order
+  OrderItemId
+);
+
+id_type!(
+  /// The merchant's SKU, named so it doesn't clash with the SKU details in
+  /// `products::MerchantSku`
+  MerchantSkuId
+);
+
+id_type!(
+  /// Jet's ID of a merchant fulfillment node (FC)
+  FulfillmentNodeId
+);
+
+#[test]
+fn test_id_conversions() {
+  use std::collections::HashMap;
+
+  let id = MerchantOrderId::from("2ab4c8b414124f0fa04072d615ec0610");
+  assert_eq!(id, "2ab4c8b414124f0fa04072d615ec0610");
+  assert_eq!(serde_json::to_string(&id).unwrap(), r#""2ab4c8b414124f0fa04072d615ec0610""#);
+  assert_eq!(serde_json::from_str::<MerchantOrderId>(r#""2ab4c8b414124f0fa04072d615ec0610""#).unwrap(), id);
+  assert_eq!(String::from(id), "2ab4c8b414124f0fa04072d615ec0610");
+
+  let mut quantities = HashMap::new();
+  quantities.insert(MerchantSkuId::from("test_product"), 2);
+  assert_eq!(quantities.get("test_product"), Some(&2));
+}
@@ -1,8 +1,26 @@
 #[macro_use]
 extern crate serde;
 
+#[cfg(feature = "async")]
+pub mod async_client;
+pub mod bulk;
+pub mod carriers;
 pub mod client;
+mod dedupe;
 pub mod error;
+pub mod ids;
+pub mod money;
+pub mod nodes;
 pub mod orders;
 pub mod products;
+mod rate_limit;
+pub mod reconcile;
+pub mod refunds;
+mod retry;
+pub mod returns;
+pub mod settlement;
+pub mod tax;
+pub mod token;
+mod trace;
 mod utils;
+pub mod webhooks;
@@ -0,0 +1,131 @@
+//! Monetary amount type used by prices, fees and refunds
+//!
+//! Amounts are `f64` by default. Enable the `decimal` feature to use
+//! `rust_decimal::Decimal` instead, which avoids float drift when
+//! reconciling settlements. Both (de)serialize as JSON numbers.
+//!
+//! With the `string-prices` feature, prices sent to Jet (`Price`,
+//! `PriceUpdate`, `NodePrice` and `RefundAmount`) are serialized as strings
+//! with exactly two decimals, e.g. `"19.90"`. Those fields, and prices
+//! read back from Jet such as `PriceResponse`, accept both numbers and
+//! strings when deserializing.
+
+use serde::de::{Deserialize, Deserializer, Error};
+use serde::ser::Serializer;
+
+#[cfg(not(feature = "decimal"))]
+pub type Money = f64;
+
+#[cfg(feature = "decimal")]
+pub type Money = rust_decimal::Decimal;
+
+/// Whether an amount, usually a difference of two amounts, is zero. With
+/// `f64` amounts below half a cent are treated as float drift.
+#[cfg(not(feature = "decimal"))]
+pub(crate) fn is_zero_amount(value: Money) -> bool {
+  value.abs() < 0.005
+}
+
+#[cfg(feature = "decimal")]
+pub(crate) fn is_zero_amount(value: Money) -> bool {
+  value.is_zero()
+}
+
+#[cfg(not(feature = "string-prices"))]
+pub(crate) fn serialize_price<S>(value: &Money, ser: S) -> Result<S::Ok, S::Error> where S: Serializer {
+  serde::Serialize::serialize(value, ser)
+}
+
+#[cfg(feature = "string-prices")]
+pub(crate) fn serialize_price<S>(value: &Money, ser: S) -> Result<S::Ok, S::Error> where S: Serializer {
+  ser.serialize_str(&format!("{:.2}", value))
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PriceRepr {
+  Number(Money),
+  Text(String),
+}
+
+impl PriceRepr {
+  fn into_money<E: Error>(self) -> Result<Money, E> {
+    match self {
+      PriceRepr::Number(value) => Ok(value),
+      PriceRepr::Text(value) => value
+        .trim()
+        .parse()
+        .map_err(|_| E::custom(format!("invalid price: '{}'", value))),
+    }
+  }
+}
+
+pub(crate) fn deserialize_price<'de, D>(de: D) -> Result<Money, D::Error> where D: Deserializer<'de> {
+  PriceRepr::deserialize(de)?.into_money()
+}
+
+pub(crate) fn deserialize_price_opt<'de, D>(de: D) -> Result<Option<Money>, D::Error> where D: Deserializer<'de> {
+  Option::<PriceRepr>::deserialize(de)?.map(PriceRepr::into_money).transpose()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_money_roundtrip() {
+    for value in ["19.99", "0.07", "1234.5"] {
+      let money: Money = serde_json::from_str(value).unwrap();
+      assert_eq!(money.to_string(), value);
+      assert_eq!(serde_json::to_string(&money).unwrap(), value);
+    }
+  }
+
+  #[derive(Serialize, Deserialize)]
+  struct Priced {
+    #[serde(serialize_with = "serialize_price", deserialize_with = "deserialize_price")]
+    price: Money,
+  }
+
+  #[test]
+  fn test_deserialize_price() {
+    for json in [r#"{"price":19.9}"#, r#"{"price":"19.9"}"#, r#"{"price":"19.90"}"#] {
+      let priced: Priced = serde_json::from_str(json).unwrap();
+      assert_eq!(priced.price, "19.9".parse::<Money>().unwrap());
+    }
+    assert!(serde_json::from_str::<Priced>(r#"{"price":"abc"}"#).is_err());
+  }
+
+  #[derive(Deserialize)]
+  struct MaybePriced {
+    #[serde(default, deserialize_with = "deserialize_price_opt")]
+    price: Option<Money>,
+  }
+
+  #[test]
+  fn test_deserialize_price_opt() {
+    for json in [r#"{"price":19.9}"#, r#"{"price":"19.90"}"#] {
+      let priced: MaybePriced = serde_json::from_str(json).unwrap();
+      assert_eq!(priced.price, Some("19.9".parse::<Money>().unwrap()));
+    }
+    for json in [r#"{"price":null}"#, "{}"] {
+      assert_eq!(serde_json::from_str::<MaybePriced>(json).unwrap().price, None);
+    }
+  }
+
+  #[cfg(feature = "string-prices")]
+  #[test]
+  fn test_serialize_price_as_string() {
+    for (value, expected) in [("19.9", r#""19.90""#), ("0.07", r#""0.07""#), ("5", r#""5.00""#)] {
+      let priced = Priced { price: value.parse().unwrap() };
+      assert_eq!(serde_json::to_value(&priced).unwrap()["price"].to_string(), expected);
+    }
+  }
+
+  #[cfg(not(feature = "string-prices"))]
+  #[test]
+  fn test_serialize_price_as_number() {
+    let priced = Priced { price: "19.9".parse().unwrap() };
+    assert_eq!(serde_json::to_string(&priced).unwrap(), r#"{"price":19.9}"#);
+  }
+}
@@ -0,0 +1,117 @@
+//! Implements merchant fulfillment node setup
+//! [Jet Documentation](https://developer.jet.com/docs/fulfillment-nodes)
+//!
+
+#[cfg(feature = "async")]
+use super::async_client::AsyncClient;
+use super::client::{Client, Method};
+use crate::error::*;
+pub use crate::ids::FulfillmentNodeId;
+use crate::orders::Address;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FulfillmentNode {
+  pub fulfillment_node_id: FulfillmentNodeId,
+  pub fulfillment_node_name: String,
+  pub address: Option<Address>,
+}
+
+impl Client {
+  /// Lists the merchant's fulfillment nodes
+  pub fn get_fulfillment_nodes(&self) -> Result<Vec<FulfillmentNode>> {
+    self.request(Method::GET, "/setup/fulfillmentNodes", std::convert::identity)
+  }
+
+  /// The merchant's fulfillment nodes, fetched on first use and cached for
+  /// the lifetime of the client and its clones
+  pub fn fulfillment_nodes(&self) -> Result<Arc<[FulfillmentNode]>> {
+    let mut cache = self.nodes.lock().expect("lock fulfillment nodes");
+    if let Some(ref nodes) = *cache {
+      return Ok(nodes.clone());
+    }
+    let nodes: Arc<[FulfillmentNode]> = self.get_fulfillment_nodes()?.into();
+    *cache = Some(nodes.clone());
+    Ok(nodes)
+  }
+
+  /// Fetches the fulfillment nodes again, replacing the cached list
+  pub fn refresh_fulfillment_nodes(&self) -> Result<Arc<[FulfillmentNode]>> {
+    let mut cache = self.nodes.lock().expect("lock fulfillment nodes");
+    let nodes: Arc<[FulfillmentNode]> = self.get_fulfillment_nodes()?.into();
+    *cache = Some(nodes.clone());
+    Ok(nodes)
+  }
+}
+
+#[cfg(feature = "async")]
+impl AsyncClient {
+  /// Lists the merchant's fulfillment nodes
+  pub async fn get_fulfillment_nodes(&self) -> Result<Vec<FulfillmentNode>> {
+    self.request(Method::GET, "/setup/fulfillmentNodes", std::convert::identity).await
+  }
+
+  /// The merchant's fulfillment nodes, fetched on first use and cached for
+  /// the lifetime of the client and its clones
+  pub async fn fulfillment_nodes(&self) -> Result<Arc<[FulfillmentNode]>> {
+    let mut cache = self.nodes.lock().await;
+    if let Some(ref nodes) = *cache {
+      return Ok(nodes.clone());
+    }
+    let nodes: Arc<[FulfillmentNode]> = self.get_fulfillment_nodes().await?.into();
+    *cache = Some(nodes.clone());
+    Ok(nodes)
+  }
+
+  /// Fetches the fulfillment nodes again, replacing the cached list
+  pub async fn refresh_fulfillment_nodes(&self) -> Result<Arc<[FulfillmentNode]>> {
+    let mut cache = self.nodes.lock().await;
+    let nodes: Arc<[FulfillmentNode]> = self.get_fulfillment_nodes().await?.into();
+    *cache = Some(nodes.clone());
+    Ok(nodes)
+  }
+}
+
+#[test]
+fn test_get_fulfillment_nodes() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", "/setup/fulfillmentNodes")
+    .with_body(r#"[{
+      "fulfillment_node_id": "b9a4a3b4e3f34b1a9b4b2a1d1c1d1e1f",
+      "fulfillment_node_name": "Hoboken",
+      "address": { "address1": "221 River St", "address2": null, "city": "Hoboken", "state": "NJ", "zip_code": "07030" }
+    }]"#)
+    .create();
+
+  let nodes = get_mock_client(&server).get_fulfillment_nodes().unwrap();
+  assert_eq!(nodes[0].fulfillment_node_id, "b9a4a3b4e3f34b1a9b4b2a1d1c1d1e1f");
+  assert_eq!(nodes[0].fulfillment_node_name, "Hoboken");
+}
+
+#[test]
+fn test_fulfillment_nodes_cached() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let nodes_mock = server
+    .mock("GET", "/setup/fulfillmentNodes")
+    .with_body(r#"[{ "fulfillment_node_id": "n1", "fulfillment_node_name": "Hoboken", "address": null }]"#)
+    .expect(2)
+    .create();
+
+  let client = get_mock_client(&server);
+  let first = client.fulfillment_nodes().unwrap();
+  let second = client.clone().fulfillment_nodes().unwrap();
+  assert!(Arc::ptr_eq(&first, &second));
+  assert_eq!(second[0].fulfillment_node_id, "n1");
+
+  let refreshed = client.refresh_fulfillment_nodes().unwrap();
+  assert!(!Arc::ptr_eq(&first, &refreshed));
+  assert!(Arc::ptr_eq(&refreshed, &client.fulfillment_nodes().unwrap()));
+  nodes_mock.assert();
+}
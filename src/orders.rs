@@ -2,32 +2,344 @@
 //! [Jet Documentation](https://developer.jet.com/docs/order-status)
 //!
 
-use super::client::{Client, Method};
-use chrono::{DateTime, Utc};
+#[cfg(feature = "async")]
+use super::async_client::AsyncClient;
+#[cfg(feature = "async")]
+use futures_util::stream::{self, Stream, StreamExt};
+use super::client::{is_html, ApiResponse, Client, Method, PreparedRequest};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+pub use crate::carriers::Carrier;
+pub use crate::ids::{MerchantOrderId, MerchantSkuId, OrderItemId};
 use crate::error::*;
-use crate::utils::serialize_datetime;
+use crate::money::{is_zero_amount, Money};
+use crate::trace;
+pub use crate::nodes::FulfillmentNodeId;
+use crate::utils::{deserialize_datetime, deserialize_datetime_opt, encode_path_segment, parallel_map, serialize_datetime};
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+/// Status of an order. Values Jet adds in the future are kept in `Unknown`
+/// instead of failing to deserialize the order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum OrderStatus {
   /// 'created' - The order has just been placed. Jet.com allows a half hour for fraud check and customer cancellation. We ask that retailers NOT fulfill orders that are created.
-  #[serde(rename = "created")]
   Created,
 
   /// 'ready' - The order is ready to be fulfilled by the retailer
-  #[serde(rename = "ready")]
   Ready,
 
   /// 'acknowledged' - The order has been accepted by the retailer and is awaiting fulfillment
-  #[serde(rename = "acknowledged")]
   Acknowledged,
 
   /// 'inprogress' - The order is partially shipped
-  #[serde(rename = "inprogress")]
   Inprogress,
 
   /// 'complete' - The order is completely shipped or cancelled. All units have been accounted for
-  #[serde(rename = "complete")]
   Complete,
+
+  /// A status this crate doesn't know, orders can't be listed by it
+  Unknown(String),
+}
+
+impl OrderStatus {
+  /// The statuses orders can be listed by
+  pub const KNOWN: [OrderStatus; 5] = [
+    OrderStatus::Created,
+    OrderStatus::Ready,
+    OrderStatus::Acknowledged,
+    OrderStatus::Inprogress,
+    OrderStatus::Complete,
+  ];
+
+  /// The status as used in the `/orders/{status}` path
+  pub fn as_str(&self) -> &str {
+    match *self {
+      OrderStatus::Created => "created",
+      OrderStatus::Ready => "ready",
+      OrderStatus::Acknowledged => "acknowledged",
+      OrderStatus::Inprogress => "inprogress",
+      OrderStatus::Complete => "complete",
+      OrderStatus::Unknown(ref value) => value,
+    }
+  }
+
+  /// Whether Jet released the order to the merchant, 'ready'. 'created'
+  /// orders may still be cancelled or fail the fraud check.
+  pub fn should_fulfill(&self) -> bool {
+    *self == OrderStatus::Ready
+  }
+
+  /// Whether the merchant has to accept or reject the order, 'ready'
+  pub fn needs_acknowledgement(&self) -> bool {
+    *self == OrderStatus::Ready
+  }
+
+  /// Whether the merchant has work left on the order: acknowledging,
+  /// shipping or cancelling units. False for 'created', 'complete' and
+  /// unknown statuses.
+  pub fn is_actionable(&self) -> bool {
+    matches!(*self, OrderStatus::Ready | OrderStatus::Acknowledged | OrderStatus::Inprogress)
+  }
+
+  /// Whether the order won't change status anymore, 'complete'
+  pub fn is_terminal(&self) -> bool {
+    *self == OrderStatus::Complete
+  }
+
+  /// The `/orders/{status}` path listing orders in this status
+  fn orders_path(&self) -> Result<String, ValidationError> {
+    match *self {
+      OrderStatus::Unknown(ref status) => Err(ValidationError::UnknownOrderStatus {
+        status: status.clone(),
+      }),
+      ref known => Ok(format!("/orders/{}", known.as_str())),
+    }
+  }
+}
+
+impl fmt::Display for OrderStatus {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl From<String> for OrderStatus {
+  fn from(value: String) -> Self {
+    value.parse().unwrap_or(OrderStatus::Unknown(value))
+  }
+}
+
+impl From<OrderStatus> for String {
+  fn from(value: OrderStatus) -> Self {
+    match value {
+      OrderStatus::Unknown(value) => value,
+      known => known.as_str().to_owned(),
+    }
+  }
+}
+
+/// Parses the known statuses, anything else is an error
+impl FromStr for OrderStatus {
+  type Err = ParseOrderStatusError;
+
+  fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+    match value {
+      "created" => Ok(OrderStatus::Created),
+      "ready" => Ok(OrderStatus::Ready),
+      "acknowledged" => Ok(OrderStatus::Acknowledged),
+      "inprogress" => Ok(OrderStatus::Inprogress),
+      "complete" => Ok(OrderStatus::Complete),
+      _ => Err(ParseOrderStatusError(value.to_owned())),
+    }
+  }
+}
+
+/// Status to let Jet know whether you accept or reject the order. Values
+/// Jet adds in the future are kept in `Other`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum AcknowledgementStatus {
+  /// 'accepted'
+  Accepted,
+  /// 'rejected - other'
+  RejectedOther,
+  /// 'rejected - fraud'
+  RejectedFraud,
+  /// 'rejected - item level error', use when the rejection is caused by
+  /// errors at the item level
+  RejectedItemLevelError,
+  /// 'rejected - ship from location not available'
+  RejectedShipFromLocationNotAvailable,
+  /// 'rejected - shipping method not supported'
+  RejectedShippingMethodNotSupported,
+  /// 'rejected - unfulfillable address'
+  RejectedUnfulfillableAddress,
+  Other(String),
+}
+
+impl AcknowledgementStatus {
+  pub fn as_str(&self) -> &str {
+    match *self {
+      AcknowledgementStatus::Accepted => "accepted",
+      AcknowledgementStatus::RejectedOther => "rejected - other",
+      AcknowledgementStatus::RejectedFraud => "rejected - fraud",
+      AcknowledgementStatus::RejectedItemLevelError => "rejected - item level error",
+      AcknowledgementStatus::RejectedShipFromLocationNotAvailable => "rejected - ship from location not available",
+      AcknowledgementStatus::RejectedShippingMethodNotSupported => "rejected - shipping method not supported",
+      AcknowledgementStatus::RejectedUnfulfillableAddress => "rejected - unfulfillable address",
+      AcknowledgementStatus::Other(ref value) => value,
+    }
+  }
+}
+
+impl From<String> for AcknowledgementStatus {
+  fn from(value: String) -> Self {
+    match value.as_str() {
+      "accepted" => AcknowledgementStatus::Accepted,
+      "rejected - other" => AcknowledgementStatus::RejectedOther,
+      "rejected - fraud" => AcknowledgementStatus::RejectedFraud,
+      "rejected - item level error" => AcknowledgementStatus::RejectedItemLevelError,
+      "rejected - ship from location not available" => AcknowledgementStatus::RejectedShipFromLocationNotAvailable,
+      "rejected - shipping method not supported" => AcknowledgementStatus::RejectedShippingMethodNotSupported,
+      "rejected - unfulfillable address" => AcknowledgementStatus::RejectedUnfulfillableAddress,
+      _ => AcknowledgementStatus::Other(value),
+    }
+  }
+}
+
+impl From<AcknowledgementStatus> for String {
+  fn from(value: AcknowledgementStatus) -> Self {
+    match value {
+      AcknowledgementStatus::Other(value) => value,
+      known => known.as_str().to_owned(),
+    }
+  }
+}
+
+/// Why the merchant rejects a whole order, each maps to one of the
+/// 'rejected - ...' acknowledgement statuses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectReason {
+  Other,
+  Fraud,
+  ItemLevelError,
+  ShipFromLocationNotAvailable,
+  ShippingMethodNotSupported,
+  UnfulfillableAddress,
+}
+
+impl RejectReason {
+  /// Status of each item when the whole order is rejected. Only an item
+  /// level error is caused by the items, marked 'nonfulfillable - no
+  /// inventory'. For every other reason the items themselves are
+  /// fulfillable, so no false inventory signal is sent.
+  pub fn item_status(self) -> ItemAcknowledgementStatus {
+    match self {
+      RejectReason::ItemLevelError => ItemAcknowledgementStatus::NonfulfillableNoInventory,
+      _ => ItemAcknowledgementStatus::Fulfillable,
+    }
+  }
+}
+
+impl From<RejectReason> for AcknowledgementStatus {
+  fn from(value: RejectReason) -> Self {
+    match value {
+      RejectReason::Other => AcknowledgementStatus::RejectedOther,
+      RejectReason::Fraud => AcknowledgementStatus::RejectedFraud,
+      RejectReason::ItemLevelError => AcknowledgementStatus::RejectedItemLevelError,
+      RejectReason::ShipFromLocationNotAvailable => AcknowledgementStatus::RejectedShipFromLocationNotAvailable,
+      RejectReason::ShippingMethodNotSupported => AcknowledgementStatus::RejectedShippingMethodNotSupported,
+      RejectReason::UnfulfillableAddress => AcknowledgementStatus::RejectedUnfulfillableAddress,
+    }
+  }
+}
+
+/// Merchant defined fulfillable or nonfulfillable status of an order item.
+/// Values Jet adds in the future are kept in `Other`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum ItemAcknowledgementStatus {
+  /// 'fulfillable'
+  Fulfillable,
+  /// 'nonfulfillable - invalid merchant SKU'
+  NonfulfillableInvalidMerchantSku,
+  /// 'nonfulfillable - no inventory'
+  NonfulfillableNoInventory,
+  Other(String),
+}
+
+impl ItemAcknowledgementStatus {
+  pub fn as_str(&self) -> &str {
+    match *self {
+      ItemAcknowledgementStatus::Fulfillable => "fulfillable",
+      ItemAcknowledgementStatus::NonfulfillableInvalidMerchantSku => "nonfulfillable - invalid merchant SKU",
+      ItemAcknowledgementStatus::NonfulfillableNoInventory => "nonfulfillable - no inventory",
+      ItemAcknowledgementStatus::Other(ref value) => value,
+    }
+  }
+}
+
+impl From<String> for ItemAcknowledgementStatus {
+  fn from(value: String) -> Self {
+    match value.as_str() {
+      "fulfillable" => ItemAcknowledgementStatus::Fulfillable,
+      "nonfulfillable - invalid merchant SKU" => ItemAcknowledgementStatus::NonfulfillableInvalidMerchantSku,
+      "nonfulfillable - no inventory" => ItemAcknowledgementStatus::NonfulfillableNoInventory,
+      _ => ItemAcknowledgementStatus::Other(value),
+    }
+  }
+}
+
+impl From<ItemAcknowledgementStatus> for String {
+  fn from(value: ItemAcknowledgementStatus) -> Self {
+    match value {
+      ItemAcknowledgementStatus::Other(value) => value,
+      known => known.as_str().to_owned(),
+    }
+  }
+}
+
+/// Exception state of an order. Values Jet adds in the future are kept
+/// in `Other`.
+///
+/// Jet's API has no call to resolve an exception, they are resolved by Jet
+/// once the merchant contacts partner support, after which the state reads
+/// `Resolved`. The quantity exceptions (`TooManyUnitsCancelled`,
+/// `TooManyUnitsShipped`) are usually fixed that way, the others only
+/// record what happened to the order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum ExceptionState {
+  /// 'exception - too many units cancelled'
+  TooManyUnitsCancelled,
+  /// 'exception - jet manual canceled to complete state'
+  JetManualCanceledToCompleteState,
+  /// 'exception - too many units shipped'
+  TooManyUnitsShipped,
+  /// 'exception - order rejected'
+  OrderRejected,
+  /// 'resolved'
+  Resolved,
+  Other(String),
+}
+
+impl ExceptionState {
+  pub fn as_str(&self) -> &str {
+    match *self {
+      ExceptionState::TooManyUnitsCancelled => "exception - too many units cancelled",
+      ExceptionState::JetManualCanceledToCompleteState => "exception - jet manual canceled to complete state",
+      ExceptionState::TooManyUnitsShipped => "exception - too many units shipped",
+      ExceptionState::OrderRejected => "exception - order rejected",
+      ExceptionState::Resolved => "resolved",
+      ExceptionState::Other(ref value) => value,
+    }
+  }
+}
+
+impl From<String> for ExceptionState {
+  fn from(value: String) -> Self {
+    match value.as_str() {
+      "exception - too many units cancelled" => ExceptionState::TooManyUnitsCancelled,
+      "exception - jet manual canceled to complete state" => ExceptionState::JetManualCanceledToCompleteState,
+      "exception - too many units shipped" => ExceptionState::TooManyUnitsShipped,
+      "exception - order rejected" => ExceptionState::OrderRejected,
+      "resolved" => ExceptionState::Resolved,
+      _ => ExceptionState::Other(value),
+    }
+  }
+}
+
+impl From<ExceptionState> for String {
+  fn from(value: ExceptionState) -> Self {
+    match value {
+      ExceptionState::Other(value) => value,
+      known => known.as_str().to_owned(),
+    }
+  }
 }
 
 /// Shipping details about the order
@@ -36,10 +348,50 @@ pub struct OrderDetail {
   pub request_shipping_carrier: Option<String>,
   pub request_shipping_method: String,
   pub request_service_level: String,
+  #[serde(deserialize_with = "deserialize_datetime")]
   pub request_ship_by: DateTime<Utc>,
+  #[serde(deserialize_with = "deserialize_datetime")]
   pub request_delivery_by: DateTime<Utc>,
 }
 
+impl OrderDetail {
+  /// Time left until the order must ship, negative once past due
+  pub fn time_until_ship_by(&self) -> Duration {
+    self.request_ship_by - Utc::now()
+  }
+
+  pub fn is_past_due(&self) -> bool {
+    self.time_until_ship_by() < Duration::zero()
+  }
+}
+
+/// How urgently an order must ship, most urgent first so sorting by it
+/// orders a work queue. See `Order::priority`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ShipByPriority {
+  /// The ship-by time has passed
+  PastDue,
+  /// Must ship within 24 hours
+  DueToday,
+  /// Must ship within 72 hours
+  DueSoon,
+  Later,
+}
+
+impl ShipByPriority {
+  fn from_time_left(left: Duration) -> ShipByPriority {
+    if left < Duration::zero() {
+      ShipByPriority::PastDue
+    } else if left < Duration::hours(24) {
+      ShipByPriority::DueToday
+    } else if left < Duration::hours(72) {
+      ShipByPriority::DueSoon
+    } else {
+      ShipByPriority::Later
+    }
+  }
+}
+
 /// Information about the buyer
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Buyer {
@@ -48,7 +400,7 @@ pub struct Buyer {
 }
 
 /// Information about the buyer
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Address {
   pub address1: String,
   pub address2: Option<String>,
@@ -64,56 +416,376 @@ pub struct ShippingTo {
   pub address: Address,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Price {
-  pub base_price: f32,
-  pub item_tax: Option<f32>,
-  pub item_shipping_cost: f32,
-  pub item_shipping_tax: Option<f32>,
+  pub base_price: Money,
+  #[serde(default)]
+  pub item_tax: Option<Money>,
+  pub item_shipping_cost: Money,
+  #[serde(default)]
+  pub item_shipping_tax: Option<Money>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Price {
+  /// Base price plus tax and shipping, missing amounts count as zero
+  pub fn total(&self) -> Money {
+    self.base_price
+      + self.item_tax.unwrap_or_default()
+      + self.item_shipping_cost
+      + self.item_shipping_tax.unwrap_or_default()
+  }
+}
+
+/// Kind of a fee adjustment. Only the values in Jet's
+/// [order docs](https://developer.jet.com/docs/order-status) are listed,
+/// anything else is kept in `Other`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum AdjustmentType {
+  /// 'Commission' - Jet's commission on the item
+  Commission,
+  /// 'Fee' - A fixed or variable fee, e.g. for payment processing
+  Fee,
+  Other(String),
+}
+
+impl AdjustmentType {
+  pub fn as_str(&self) -> &str {
+    match *self {
+      AdjustmentType::Commission => "Commission",
+      AdjustmentType::Fee => "Fee",
+      AdjustmentType::Other(ref value) => value,
+    }
+  }
+}
+
+impl From<String> for AdjustmentType {
+  fn from(value: String) -> Self {
+    match value.as_str() {
+      "Commission" => AdjustmentType::Commission,
+      "Fee" => AdjustmentType::Fee,
+      _ => AdjustmentType::Other(value),
+    }
+  }
+}
+
+impl From<AdjustmentType> for String {
+  fn from(value: AdjustmentType) -> Self {
+    match value {
+      AdjustmentType::Other(value) => value,
+      known => known.as_str().to_owned(),
+    }
+  }
+}
+
+/// Why the price of an order item was adjusted. Only the values in Jet's
+/// [order docs](https://developer.jet.com/docs/order-status) are listed,
+/// anything else is kept in `Other`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum AdjustmentReason {
+  /// 'price override' - The merchant or Jet overrode the listed price
+  PriceOverride,
+  /// 'item damaged' - Partial refund for an item that arrived damaged
+  ItemDamaged,
+  Other(String),
+}
+
+impl AdjustmentReason {
+  pub fn as_str(&self) -> &str {
+    match *self {
+      AdjustmentReason::PriceOverride => "price override",
+      AdjustmentReason::ItemDamaged => "item damaged",
+      AdjustmentReason::Other(ref value) => value,
+    }
+  }
+}
+
+impl From<String> for AdjustmentReason {
+  fn from(value: String) -> Self {
+    match value.as_str() {
+      "price override" => AdjustmentReason::PriceOverride,
+      "item damaged" => AdjustmentReason::ItemDamaged,
+      _ => AdjustmentReason::Other(value),
+    }
+  }
+}
+
+impl From<AdjustmentReason> for String {
+  fn from(value: AdjustmentReason) -> Self {
+    match value {
+      AdjustmentReason::Other(value) => value,
+      known => known.as_str().to_owned(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FeeAdjustment {
   pub adjustment_name: String,
-  pub adjustment_type: String,
+  pub adjustment_type: AdjustmentType,
   pub commission_id: String,
-  pub value: f32,
+  pub value: Money,
 }
 
+/// Jet omits some amounts and sends `null` for others, both deserialize as
+/// `None` here and in `Price` and `OrderItem`
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OrderTotals {
+  #[serde(default)]
   pub item_price: Option<Price>,
-  pub item_fees: Option<f32>,
+  #[serde(default)]
+  pub item_fees: Option<Money>,
+  #[serde(default)]
   pub fee_adjustments: Option<Vec<FeeAdjustment>>,
-  pub regulatory_fees: Option<f32>,
+  #[serde(default)]
+  pub regulatory_fees: Option<Money>,
+  /// Tax of the whole order. Jet usually only sends it per item, see
+  /// `Order::computed_totals`.
+  #[serde(default)]
+  pub tax_info: Option<TaxInfo>,
+}
+
+/// Differences between the `order_totals` Jet reported and the sums of the
+/// order items, see `Order::reconcile`. Each field is reported minus
+/// computed, missing amounts count as zero.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TotalsDiscrepancy {
+  pub base_price: Money,
+  pub item_tax: Money,
+  pub item_shipping_cost: Money,
+  pub item_shipping_tax: Money,
+  pub item_fees: Money,
+  pub fee_adjustments: Money,
+  pub regulatory_fees: Money,
+}
+
+impl TotalsDiscrepancy {
+  /// Whether every delta is zero. With the default `f64` money type deltas
+  /// below half a cent are treated as float drift.
+  pub fn is_balanced(&self) -> bool {
+    [
+      self.base_price,
+      self.item_tax,
+      self.item_shipping_cost,
+      self.item_shipping_tax,
+      self.item_fees,
+      self.fee_adjustments,
+      self.regulatory_fees,
+    ]
+    .iter()
+    .all(|delta| is_zero_amount(*delta))
+  }
+}
+
+/// Level of government a tax is collected for. Values Jet adds in the
+/// future are kept in `Other`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum JurisdictionType {
+  /// 'state'
+  State,
+  /// 'county'
+  County,
+  /// 'city'
+  City,
+  /// 'special' - special purpose districts, e.g. transit
+  Special,
+  Other(String),
+}
+
+impl JurisdictionType {
+  pub fn as_str(&self) -> &str {
+    match *self {
+      JurisdictionType::State => "state",
+      JurisdictionType::County => "county",
+      JurisdictionType::City => "city",
+      JurisdictionType::Special => "special",
+      JurisdictionType::Other(ref value) => value,
+    }
+  }
+}
+
+impl From<String> for JurisdictionType {
+  fn from(value: String) -> Self {
+    match value.to_ascii_lowercase().as_str() {
+      "state" => JurisdictionType::State,
+      "county" => JurisdictionType::County,
+      "city" => JurisdictionType::City,
+      "special" => JurisdictionType::Special,
+      _ => JurisdictionType::Other(value),
+    }
+  }
+}
+
+impl From<JurisdictionType> for String {
+  fn from(value: JurisdictionType) -> Self {
+    match value {
+      JurisdictionType::Other(value) => value,
+      known => known.as_str().to_owned(),
+    }
+  }
+}
+
+/// Tax collected for one jurisdiction. The shape is assumed, Jet's order
+/// docs and the captured payloads don't include per-jurisdiction tax.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxJurisdiction {
+  pub jurisdiction_type: JurisdictionType,
+  /// e.g. 'NY' or 'Kings County'
+  pub jurisdiction_name: Option<String>,
+  #[serde(default)]
+  pub tax_rate: Option<Money>,
+  pub tax_amount: Money,
+}
+
+/// Tax details of an order item, split by jurisdiction
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaxInfo {
+  pub tax_code: Option<String>,
+  pub state_tax: Option<Money>,
+  pub county_tax: Option<Money>,
+  pub city_tax: Option<Money>,
+  pub special_tax: Option<Money>,
+  /// Whether the item was sold tax exempt. Speculative, not in Jet's
+  /// order docs or the captured payloads.
+  #[serde(default)]
+  pub tax_exempt: Option<bool>,
+  /// Tax collected per jurisdiction. Speculative like `tax_exempt`, empty
+  /// unless Jet sends it.
+  #[serde(default)]
+  pub jurisdictions: Vec<TaxJurisdiction>,
+}
+
+impl TaxInfo {
+  /// Total tax collected: the sum of `jurisdictions`, or of the per-level
+  /// amounts if Jet sent no jurisdictions
+  pub fn total_tax(&self) -> Money {
+    if !self.jurisdictions.is_empty() {
+      return self.jurisdictions.iter().map(|jurisdiction| jurisdiction.tax_amount).sum();
+    }
+    [self.state_tax, self.county_tax, self.city_tax, self.special_tax]
+      .iter()
+      .flatten()
+      .copied()
+      .sum()
+  }
+
+  /// The per-level amounts as unnamed jurisdictions
+  fn level_jurisdictions(&self) -> Vec<TaxJurisdiction> {
+    vec![
+      (JurisdictionType::State, self.state_tax),
+      (JurisdictionType::County, self.county_tax),
+      (JurisdictionType::City, self.city_tax),
+      (JurisdictionType::Special, self.special_tax),
+    ]
+    .into_iter()
+    .filter_map(|(jurisdiction_type, amount)| {
+      amount.map(|tax_amount| TaxJurisdiction {
+        jurisdiction_type,
+        jurisdiction_name: None,
+        tax_rate: None,
+        tax_amount,
+      })
+    })
+    .collect()
+  }
+
+  /// Sums the tax of several items. Per-level amounts are added up and
+  /// jurisdictions with the same type and name are merged. If only some
+  /// items have jurisdictions, the others' per-level amounts are merged in
+  /// as unnamed jurisdictions so `total_tax` counts every item. The tax
+  /// code is kept only if every item has the same one, and the result is
+  /// tax exempt only if every item is.
+  pub fn aggregate<'a, I: IntoIterator<Item = &'a TaxInfo>>(infos: I) -> TaxInfo {
+    fn add(total: &mut Option<Money>, value: Option<Money>) {
+      if let Some(value) = value {
+        *total = Some(total.unwrap_or_default() + value);
+      }
+    }
+
+    let infos: Vec<&TaxInfo> = infos.into_iter().collect();
+    let itemized = infos.iter().any(|info| !info.jurisdictions.is_empty());
+    let mut total = TaxInfo::default();
+    for (index, info) in infos.into_iter().enumerate() {
+      if index == 0 {
+        total.tax_code = info.tax_code.clone();
+        total.tax_exempt = info.tax_exempt;
+      } else {
+        if total.tax_code != info.tax_code {
+          total.tax_code = None;
+        }
+        total.tax_exempt = match (total.tax_exempt, info.tax_exempt) {
+          (Some(a), Some(b)) => Some(a && b),
+          _ => None,
+        };
+      }
+      add(&mut total.state_tax, info.state_tax);
+      add(&mut total.county_tax, info.county_tax);
+      add(&mut total.city_tax, info.city_tax);
+      add(&mut total.special_tax, info.special_tax);
+      let levels;
+      let jurisdictions = if itemized && info.jurisdictions.is_empty() {
+        levels = info.level_jurisdictions();
+        &levels
+      } else {
+        &info.jurisdictions
+      };
+      for jurisdiction in jurisdictions {
+        let existing = total.jurisdictions.iter_mut().find(|existing| {
+          existing.jurisdiction_type == jurisdiction.jurisdiction_type
+            && existing.jurisdiction_name == jurisdiction.jurisdiction_name
+        });
+        match existing {
+          Some(existing) => {
+            existing.tax_amount += jurisdiction.tax_amount;
+            if existing.tax_rate != jurisdiction.tax_rate {
+              existing.tax_rate = None;
+            }
+          }
+          None => total.jurisdictions.push(jurisdiction.clone()),
+        }
+      }
+    }
+    total
+  }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OrderItem {
-  pub order_item_id: String,
+  pub order_item_id: OrderItemId,
   pub alt_order_item_id: Option<String>,
-  pub merchant_sku: String,
+  pub merchant_sku: MerchantSkuId,
   pub product_title: String,
   pub request_order_quantity: i32,
-  pub adjustment_reason: Option<String>,
+  pub adjustment_reason: Option<AdjustmentReason>,
   pub item_tax_code: Option<String>,
   pub url: String,
-  pub price_adjustment: Option<f32>,
-  pub item_fees: Option<f32>,
+  #[serde(default)]
+  pub price_adjustment: Option<Money>,
+  #[serde(default)]
+  pub item_fees: Option<Money>,
+  #[serde(default)]
   pub fee_adjustments: Option<Vec<FeeAdjustment>>,
-  // pub tax_info: Tax,
-  pub regulatory_fees: Option<f32>,
+  #[serde(default)]
+  pub tax_info: Option<TaxInfo>,
+  #[serde(default)]
+  pub regulatory_fees: Option<Money>,
   pub item_price: Price,
 
   /// When an order moves from "ready" to "acknowledged"
-  pub order_item_acknowledgement_status: Option<String>,
+  pub order_item_acknowledgement_status: Option<ItemAcknowledgementStatus>,
+
+  /// Fields Jet sent that this crate doesn't model yet
+  #[serde(flatten)]
+  pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ShipmentItem {
   pub shipment_item_id: Option<String>,
   pub alt_shipment_item_id: Option<String>,
-  pub merchant_sku: String,
+  pub merchant_sku: MerchantSkuId,
   pub response_shipment_sku_quantity: i32,
   pub response_shipment_cancel_qty: Option<i32>,
   #[serde(rename = "RMA_number")]
@@ -127,38 +799,42 @@ pub struct Shipment {
   pub shipment_id: String,
   pub alt_shipment_id: Option<String>,
   pub shipment_tracking_number: Option<String>,
+  #[serde(default, deserialize_with = "deserialize_datetime_opt")]
   pub response_shipment_date: Option<DateTime<Utc>>,
   pub response_shipment_method: Option<String>,
+  #[serde(default, deserialize_with = "deserialize_datetime_opt")]
   pub expected_delivery_date: Option<DateTime<Utc>>,
   pub ship_from_zip_code: Option<String>,
-  pub carrier: String,
+  pub carrier: Carrier,
+  #[serde(default, deserialize_with = "deserialize_datetime_opt")]
   pub carrier_pick_up_date: Option<DateTime<Utc>>,
   pub shipment_items: Vec<ShipmentItem>,
+
+  /// Fields Jet sent that this crate doesn't model yet
+  #[serde(flatten)]
+  pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Order {
   /// Jet's unique ID for a given merchant order.
-  pub merchant_order_id: String,
+  pub merchant_order_id: MerchantOrderId,
   /// Jet's human readable order ID number that may have a small chance of collision overtime.
+  /// Sub-orders split from the same customer order share it.
   pub reference_order_id: String,
   pub customer_reference_order_id: String,
   /// The fulfillment node that the order should be shipped from.
-  pub fulfillment_node: String,
+  pub fulfillment_node: FulfillmentNodeId,
   /// Optional Merchant supplied order ID.If an alt_order_id has been associated with the merchant_order_id via the order accept message this will be passed as well.
   pub alt_order_id: Option<String>,
   /// The email hash assigned by Jet to be used as the customer email address
   pub hash_email: String,
   /// Current status of the order
   pub status: OrderStatus,
-  /// Must be one of the following values:
-  /// - exception - too many units cancelled
-  /// - exception - jet manual canceled to complete state
-  /// - exception - too many units shipped
-  /// - exception - order rejected
-  /// - resolved
-  pub exception_state: Option<String>,
+  /// Exception state of the order, if any
+  pub exception_state: Option<ExceptionState>,
   /// The date the merchant order was placed.
+  #[serde(deserialize_with = "deserialize_datetime")]
   pub order_placed_date: DateTime<Utc>,
   /// Shipping details about the order
   pub order_detail: OrderDetail,
@@ -170,158 +846,1005 @@ pub struct Order {
   pub order_items: Vec<OrderItem>,
 
   // When an order moves from "created" to "ready"
+  #[serde(default, deserialize_with = "deserialize_datetime_opt")]
   pub order_ready_date: Option<DateTime<Utc>>,
   pub has_shipments: bool,
 
   // When an order moves from "ready" to "acknowledged", the following fields are added
+  #[serde(default, deserialize_with = "deserialize_datetime_opt")]
   pub order_acknowledge_date: Option<DateTime<Utc>>,
   /// Status to let Jet know whether you accept or reject the order.
   /// Errors that occur at the item level should be given the status
   // 'rejected - item level error'. This is returned in the order acknowledgement message.
-  pub acknowledgement_status: Option<String>,
+  pub acknowledgement_status: Option<AcknowledgementStatus>,
 
   // The following fields are provided by the merchant through the shipped message.
   // If multiple shipped messages are sent, shipment objects will be aggregated into the same shipments array
   pub shipments: Option<Vec<Shipment>>,
-}
 
-#[derive(Debug, Deserialize)]
-pub struct GetOrdersResponse {
-  pub order_urls: Vec<String>,
-}
+  /// Comments the customer left with the order, e.g. delivery instructions
+  pub customer_order_comments: Option<String>,
 
-#[derive(Debug, Serialize)]
-pub struct AcknowledgeOrderItem {
-  /// Merchant defined fulfillable or nonfulfillable skus within the order.
-  /// Must be one of the following values:
-  /// - nonfulfillable - invalid merchant SKU
-  /// - nonfulfillable - no inventory
-  /// - fulfillable
-  pub order_item_acknowledgement_status: &'static str,
-  /// Jet's unique identifier for an item in a merchant order.
-  pub order_item_id: String,
-  /// Optional seller-supplied ID for an item in an order.
-  /// If this value is specified with the Jet's order_item_id,
-  /// Jet will map the two IDs and you can then use your own
-  /// order item ID for subsequent feeds relating to that order item.
-  pub alt_order_item_id: Option<String>,
-}
+  /// When an order is fulfilled from several nodes Jet splits it into
+  /// sub-orders, each with its own merchant_order_id, listed here.
+  pub sub_orders: Option<Vec<String>>,
 
-#[derive(Debug, Serialize)]
-pub struct AcknowledgeOrder {
-  /// Status to let Jet know whether you accept or reject the order.
-  /// Must be one of the following values:
-  /// - rejected - other
-  /// - rejected - fraud
-  /// - rejected - item level error
-  /// - rejected - ship from location not available
-  /// - rejected - shipping method not supported
-  /// - rejected - unfulfillable address
-  /// - accepted
-  pub acknowledgement_status: &'static str,
-  pub alt_order_id: Option<String>,
-  pub order_items: Vec<AcknowledgeOrderItem>,
+  /// Fields Jet sent that this crate doesn't model yet
+  #[serde(flatten)]
+  pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct ShipOrderShipmentItem {
-  pub merchant_sku: String,
-  pub response_shipment_sku_quantity: i32,
-  pub days_to_return: i32,
+/// What changed between two fetches of the same order, see `Order::diff`.
+/// Changed fields hold the previous and the current value.
+#[derive(Debug)]
+pub struct OrderChanges<'a> {
+  pub status: Option<(OrderStatus, OrderStatus)>,
+  pub exception_state: Option<(Option<ExceptionState>, Option<ExceptionState>)>,
+  pub acknowledgement_status: Option<(Option<AcknowledgementStatus>, Option<AcknowledgementStatus>)>,
+  /// Shipments whose shipment_id wasn't in the previous order
+  pub new_shipments: Vec<&'a Shipment>,
+  /// `remaining_quantities` of the SKUs where they differ
+  pub remaining_quantities: HashMap<MerchantSkuId, (i32, i32)>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct ShipOrderShipment {
-  pub carrier: String,
-  pub shipment_tracking_number: Option<String>,
-  pub shipment_items: Vec<ShipOrderShipmentItem>,
-  #[serde(serialize_with = "serialize_datetime")]
-  pub response_shipment_date: DateTime<Utc>,
+impl OrderChanges<'_> {
+  pub fn is_empty(&self) -> bool {
+    self.status.is_none()
+      && self.exception_state.is_none()
+      && self.acknowledgement_status.is_none()
+      && self.new_shipments.is_empty()
+      && self.remaining_quantities.is_empty()
+  }
 }
 
-#[derive(Debug, Serialize)]
-pub struct ShipOrder {
-  pub alt_order_id: Option<String>,
-  pub shipments: Vec<ShipOrderShipment>,
+fn changed<T: PartialEq + Clone>(previous: &T, current: &T) -> Option<(T, T)> {
+  if previous == current {
+    None
+  } else {
+    Some((previous.clone(), current.clone()))
+  }
 }
 
-impl Client {
-  pub fn get_orders(&self, status: OrderStatus) -> Result<GetOrdersResponse> {
-    self.request(
-      Method::GET,
-      &format!(
-        "/orders/{}",
-        match status {
-          OrderStatus::Created => "created",
-          OrderStatus::Ready => "ready",
-          OrderStatus::Acknowledged => "acknowledged",
-          OrderStatus::Inprogress => "inprogress",
-          OrderStatus::Complete => "complete",
-        }
-      ),
-      std::convert::identity,
-    )
+impl Order {
+  /// Compares the order against an earlier fetch of it, e.g. to detect
+  /// status transitions and new shipments when polling
+  pub fn diff<'a>(&'a self, previous: &Order) -> OrderChanges<'a> {
+    let previous_shipments: Vec<&str> = previous
+      .shipments
+      .iter()
+      .flatten()
+      .map(|shipment| shipment.shipment_id.as_str())
+      .collect();
+    let previous_remaining = previous.remaining_quantities();
+    let current_remaining = self.remaining_quantities();
+    let mut remaining_quantities = HashMap::new();
+    for sku in previous_remaining.keys().chain(current_remaining.keys()) {
+      let before = previous_remaining.get(sku).cloned().unwrap_or(0);
+      let after = current_remaining.get(sku).cloned().unwrap_or(0);
+      if before != after {
+        remaining_quantities.insert(sku.clone(), (before, after));
+      }
+    }
+
+    OrderChanges {
+      status: changed(&previous.status, &self.status),
+      exception_state: changed(&previous.exception_state, &self.exception_state),
+      acknowledgement_status: changed(&previous.acknowledgement_status, &self.acknowledgement_status),
+      new_shipments: self
+        .shipments
+        .iter()
+        .flatten()
+        .filter(|shipment| !previous_shipments.contains(&shipment.shipment_id.as_str()))
+        .collect(),
+      remaining_quantities,
+    }
   }
 
-  pub fn get_order_detail(&self, order_url: &str) -> Result<Order> {
-    self.request(Method::GET, order_url, std::convert::identity)
+  /// Bucket of `order_detail.request_ship_by` relative to now
+  pub fn priority(&self) -> ShipByPriority {
+    ShipByPriority::from_time_left(self.order_detail.time_until_ship_by())
   }
 
-  pub fn acknowledge_order(&self, order_id: &str, ack: &AcknowledgeOrder) -> Result<()> {
-    self.request_no_content(
-      Method::PUT,
-      &format!("/orders/{}/acknowledge", order_id),
-      |req| {
-        req.json(ack)
-      },
-    )
+  /// See `OrderStatus::should_fulfill`
+  pub fn should_fulfill(&self) -> bool {
+    self.status.should_fulfill()
   }
 
-  pub fn ship_order(&self, order_id: &str, ship: &ShipOrder) -> Result<()> {
-    self.request_no_content(
-      Method::PUT,
-      &format!("/orders/{}/shipped", order_id),
-      |req| {
-        req.json(ship)
-      },
-    )
+  /// Whether the order is 'ready' and wasn't acknowledged yet
+  pub fn needs_acknowledgement(&self) -> bool {
+    self.status.needs_acknowledgement() && self.acknowledgement_status.is_none()
   }
-}
 
-#[test]
-fn test_get_orders() {
-  use crate::client::get_test_client;
-  let client = get_test_client();
-  println!("{:#?}", client.get_orders(OrderStatus::Ready).unwrap());
-}
+  /// See `OrderStatus::is_actionable`
+  pub fn is_actionable(&self) -> bool {
+    self.status.is_actionable()
+  }
 
-#[test]
-fn test_get_order_detail() {
-  use crate::client::get_test_client;
-  let client = get_test_client();
-  println!(
-    "{:#?}",
-    client
-      .get_order_detail("/orders/withoutShipmentDetail/2ab4c8b414124f0fa04072d615ec0610")
-      .unwrap()
-  );
-}
+  /// See `OrderStatus::is_terminal`
+  pub fn is_terminal(&self) -> bool {
+    self.status.is_terminal()
+  }
 
-#[test]
-fn test_acknowledge_order() {
-  use crate::client::get_test_client;
-  let client = get_test_client();
+  /// One line with the order ID, status, unit count, total charged and
+  /// ship-by time, for logs. Use `Debug` for everything.
+  pub fn summary(&self) -> String {
+    let units: i32 = self.order_items.iter().map(|item| item.request_order_quantity).sum();
+    let total = match self.order_totals.item_price {
+      Some(ref price) => price.total(),
+      None => self.computed_totals().item_price.map_or_else(Money::default, |price| price.total()),
+    };
+    format!(
+      "order {} ({}): {}, {} units, total {:.2}, ship by {}",
+      self.merchant_order_id,
+      self.reference_order_id,
+      self.status.as_str(),
+      units,
+      total,
+      self.order_detail.request_ship_by.to_rfc3339_opts(SecondsFormat::Secs, true),
+    )
+  }
+
+  /// The first item with `merchant_sku`, see `items_by_sku` for orders
+  /// listing a SKU more than once
+  pub fn item_by_sku(&self, merchant_sku: &str) -> Option<&OrderItem> {
+    self.order_items.iter().find(|item| item.merchant_sku == merchant_sku)
+  }
+
+  pub fn items_by_sku<'a>(&'a self, merchant_sku: &'a str) -> impl Iterator<Item = &'a OrderItem> + 'a {
+    self.order_items.iter().filter(move |item| item.merchant_sku == merchant_sku)
+  }
+
+  pub fn item_by_order_item_id(&self, order_item_id: &str) -> Option<&OrderItem> {
+    self.order_items.iter().find(|item| item.order_item_id == order_item_id)
+  }
+
+  /// Units per merchant_sku still to be shipped or cancelled: the ordered
+  /// quantity minus the quantities shipped and cancelled across all
+  /// shipments
+  pub fn remaining_quantities(&self) -> HashMap<MerchantSkuId, i32> {
+    let mut remaining = HashMap::new();
+    for item in &self.order_items {
+      *remaining.entry(item.merchant_sku.clone()).or_insert(0) += item.request_order_quantity;
+    }
+    for shipment in self.shipments.iter().flatten() {
+      for item in &shipment.shipment_items {
+        *remaining.entry(item.merchant_sku.clone()).or_insert(0) -=
+          item.response_shipment_sku_quantity + item.response_shipment_cancel_qty.unwrap_or(0);
+      }
+    }
+    remaining
+  }
+
+  /// Order totals summed from the order items. Every amount is set, using
+  /// zero where the items don't have it, and `tax_info` aggregates the
+  /// items' tax info.
+  pub fn computed_totals(&self) -> OrderTotals {
+    let items = &self.order_items;
+    let sum = |f: &dyn Fn(&OrderItem) -> Option<Money>| -> Money {
+      items.iter().filter_map(f).sum()
+    };
+    OrderTotals {
+      item_price: Some(Price {
+        base_price: sum(&|item| Some(item.item_price.base_price)),
+        item_tax: Some(sum(&|item| item.item_price.item_tax)),
+        item_shipping_cost: sum(&|item| Some(item.item_price.item_shipping_cost)),
+        item_shipping_tax: Some(sum(&|item| item.item_price.item_shipping_tax)),
+      }),
+      item_fees: Some(sum(&|item| item.item_fees)),
+      fee_adjustments: Some(
+        items
+          .iter()
+          .flat_map(|item| item.fee_adjustments.iter().flatten().cloned())
+          .collect(),
+      ),
+      regulatory_fees: Some(sum(&|item| item.regulatory_fees)),
+      tax_info: Some(TaxInfo::aggregate(items.iter().filter_map(|item| item.tax_info.as_ref()))),
+    }
+  }
+
+  /// Compares `order_totals` against `computed_totals`, use
+  /// `TotalsDiscrepancy::is_balanced` to check for mismatches
+  pub fn reconcile(&self) -> TotalsDiscrepancy {
+    fn price_parts(totals: &OrderTotals) -> [Money; 4] {
+      totals.item_price.as_ref().map_or([Money::default(); 4], |price| {
+        [
+          price.base_price,
+          price.item_tax.unwrap_or_default(),
+          price.item_shipping_cost,
+          price.item_shipping_tax.unwrap_or_default(),
+        ]
+      })
+    }
+    fn adjustments(totals: &OrderTotals) -> Money {
+      totals.fee_adjustments.iter().flatten().map(|fee| fee.value).sum()
+    }
+
+    let reported = &self.order_totals;
+    let computed = self.computed_totals();
+    let reported_price = price_parts(reported);
+    let computed_price = price_parts(&computed);
+    TotalsDiscrepancy {
+      base_price: reported_price[0] - computed_price[0],
+      item_tax: reported_price[1] - computed_price[1],
+      item_shipping_cost: reported_price[2] - computed_price[2],
+      item_shipping_tax: reported_price[3] - computed_price[3],
+      item_fees: reported.item_fees.unwrap_or_default() - computed.item_fees.unwrap_or_default(),
+      fee_adjustments: adjustments(reported) - adjustments(&computed),
+      regulatory_fees: reported.regulatory_fees.unwrap_or_default()
+        - computed.regulatory_fees.unwrap_or_default(),
+    }
+  }
+}
+
+/// Query parameters for `get_orders_with`. Unset fields use Jet's defaults.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct GetOrdersOptions {
+  /// Include orders Jet has archived
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub include_archived: Option<bool>,
+  /// Maximum number of order URLs returned
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetOrdersResponse {
+  pub order_urls: Vec<String>,
+  /// Set when Jet truncated `order_urls`, a GET of this path returns the
+  /// next page in the same shape. See `get_all_order_urls`.
+  #[serde(default)]
+  pub next_page_url: Option<String>,
+}
+
+impl GetOrdersResponse {
+  /// The merchant_order_ids of `order_urls`, see `order_id_from_url`. URLs
+  /// without an ID are skipped, and logged with the `tracing` feature.
+  pub fn order_ids(&self) -> Vec<MerchantOrderId> {
+    self
+      .order_urls
+      .iter()
+      .filter_map(|url| {
+        let id = order_id_from_url(url);
+        if id.is_none() {
+          trace::unparsed_order_url(url);
+        }
+        id.map(MerchantOrderId::from)
+      })
+      .collect()
+  }
+}
+
+/// Deserializes a `GetOrdersResponse` body, handing each order URL to the
+/// callback instead of collecting them. Yields the `next_page_url`.
+struct OrderUrlsSeed<'f, F>(&'f mut F);
+
+impl<'de, 'f, F: FnMut(String)> DeserializeSeed<'de> for OrderUrlsSeed<'f, F> {
+  type Value = Option<String>;
+
+  fn deserialize<D: Deserializer<'de>>(self, de: D) -> std::result::Result<Option<String>, D::Error> {
+    de.deserialize_map(self)
+  }
+}
+
+impl<'de, 'f, F: FnMut(String)> Visitor<'de> for OrderUrlsSeed<'f, F> {
+  type Value = Option<String>;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("an object with order_urls")
+  }
+
+  fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Option<String>, A::Error> {
+    let mut next_page_url = None;
+    while let Some(key) = map.next_key::<String>()? {
+      match key.as_str() {
+        "order_urls" => map.next_value_seed(UrlsSeed(&mut *self.0))?,
+        "next_page_url" => next_page_url = map.next_value()?,
+        _ => {
+          map.next_value::<IgnoredAny>()?;
+        }
+      }
+    }
+    Ok(next_page_url)
+  }
+}
+
+struct UrlsSeed<'f, F>(&'f mut F);
+
+impl<'de, 'f, F: FnMut(String)> DeserializeSeed<'de> for UrlsSeed<'f, F> {
+  type Value = ();
+
+  fn deserialize<D: Deserializer<'de>>(self, de: D) -> std::result::Result<(), D::Error> {
+    de.deserialize_seq(self)
+  }
+}
+
+impl<'de, 'f, F: FnMut(String)> Visitor<'de> for UrlsSeed<'f, F> {
+  type Value = ();
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("a list of order URLs")
+  }
+
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<(), A::Error> {
+    while let Some(url) = seq.next_element::<String>()? {
+      (self.0)(url);
+    }
+    Ok(())
+  }
+}
+
+/// Detail URL of an order by its merchant_order_id
+fn order_url(order_id: &str) -> String {
+  format!("/orders/withoutShipmentDetail/{}", order_id)
+}
+
+/// Detail URL of an order by the alt_order_id the merchant sent with the
+/// acknowledgement
+fn alt_order_url(alt_order_id: &str) -> String {
+  format!("/orders/alt_order_id/{}", encode_path_segment(alt_order_id))
+}
+
+/// Extracts the merchant_order_id from an order URL returned by `get_orders`,
+/// e.g. `/orders/withoutShipmentDetail/2ab4c8b414124f0fa04072d615ec0610`
+pub fn order_id_from_url(url: &str) -> Option<&str> {
+  let id = url.trim_end_matches('/').rsplit('/').next()?;
+  if !id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit()) {
+    Some(id)
+  } else {
+    None
+  }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AcknowledgeOrderItem {
+  /// Merchant defined fulfillable or nonfulfillable skus within the order.
+  pub order_item_acknowledgement_status: ItemAcknowledgementStatus,
+  /// Jet's unique identifier for an item in a merchant order.
+  pub order_item_id: OrderItemId,
+  /// Optional seller-supplied ID for an item in an order.
+  /// If this value is specified with the Jet's order_item_id,
+  /// Jet will map the two IDs and you can then use your own
+  /// order item ID for subsequent feeds relating to that order item.
+  pub alt_order_item_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AcknowledgeOrder {
+  /// Status to let Jet know whether you accept or reject the order.
+  pub acknowledgement_status: AcknowledgementStatus,
+  pub alt_order_id: Option<String>,
+  pub order_items: Vec<AcknowledgeOrderItem>,
+}
+
+impl AcknowledgeOrder {
+  /// Acknowledges every item of `order`, with the statuses in
+  /// `rejections` for the listed SKUs and fulfillable for the rest. The
+  /// order is accepted unless no item is fulfillable, then it is rejected
+  /// with 'rejected - item level error'.
+  pub fn partial(
+    order: &Order,
+    rejections: &[(&str, ItemAcknowledgementStatus)],
+  ) -> Result<AcknowledgeOrder, ValidationError> {
+    if let Some((merchant_sku, _)) = rejections.iter().find(|(sku, _)| order.item_by_sku(sku).is_none()) {
+      return Err(ValidationError::UnknownSku {
+        merchant_sku: (*merchant_sku).to_owned(),
+      });
+    }
+    let order_items: Vec<AcknowledgeOrderItem> = order
+      .order_items
+      .iter()
+      .map(|item| AcknowledgeOrderItem {
+        order_item_acknowledgement_status: rejections
+          .iter()
+          .find(|(sku, _)| item.merchant_sku == *sku)
+          .map_or(ItemAcknowledgementStatus::Fulfillable, |(_, status)| status.clone()),
+        order_item_id: item.order_item_id.clone(),
+        alt_order_item_id: item.alt_order_item_id.clone(),
+      })
+      .collect();
+    let any_fulfillable = order_items
+      .iter()
+      .any(|item| item.order_item_acknowledgement_status == ItemAcknowledgementStatus::Fulfillable);
+    Ok(AcknowledgeOrder {
+      acknowledgement_status: if any_fulfillable {
+        AcknowledgementStatus::Accepted
+      } else {
+        AcknowledgementStatus::RejectedItemLevelError
+      },
+      alt_order_id: order.alt_order_id.clone(),
+      order_items,
+    })
+  }
+
+  /// Rejects every item of `order` for `reason`. Jet requires an item
+  /// status even when the whole order is rejected, see
+  /// `RejectReason::item_status`. To reject only some items, use
+  /// `AcknowledgeOrder::partial`.
+  pub fn reject(order: &Order, reason: RejectReason) -> AcknowledgeOrder {
+    AcknowledgeOrder {
+      acknowledgement_status: reason.into(),
+      alt_order_id: order.alt_order_id.clone(),
+      order_items: order
+        .order_items
+        .iter()
+        .map(|item| AcknowledgeOrderItem {
+          order_item_acknowledgement_status: reason.item_status(),
+          order_item_id: item.order_item_id.clone(),
+          alt_order_item_id: item.alt_order_item_id.clone(),
+        })
+        .collect(),
+    }
+  }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShipOrderShipmentItem {
+  pub merchant_sku: MerchantSkuId,
+  pub response_shipment_sku_quantity: i32,
+  pub days_to_return: i32,
+  /// Where the customer should return the item, if not the merchant's
+  /// default return address
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub return_location: Option<Address>,
+  /// Return merchandise authorization number to route returns
+  #[serde(rename = "RMA_number", skip_serializing_if = "Option::is_none")]
+  pub rma_number: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShipOrderShipment {
+  pub carrier: Carrier,
+  pub shipment_tracking_number: Option<String>,
+  pub shipment_items: Vec<ShipOrderShipmentItem>,
+  #[serde(serialize_with = "serialize_datetime")]
+  pub response_shipment_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShipOrder {
+  pub alt_order_id: Option<String>,
+  pub shipments: Vec<ShipOrderShipment>,
+}
+
+impl ShipOrder {
+  pub fn builder() -> ShipOrderBuilder {
+    ShipOrderBuilder::default()
+  }
+
+  /// Checks the shipments for mistakes Jet would reject: no shipments, a
+  /// shipment without items or carrier, or negative quantities
+  pub fn validate(&self) -> Result<(), ValidationError> {
+    if self.shipments.is_empty() {
+      return Err(ValidationError::NoShipments);
+    }
+    for (index, shipment) in self.shipments.iter().enumerate() {
+      if shipment.carrier.as_str().trim().is_empty() {
+        return Err(ValidationError::EmptyCarrier { shipment: index });
+      }
+      if shipment.shipment_items.is_empty() {
+        return Err(ValidationError::NoShipmentItems { shipment: index });
+      }
+      if let Some(item) = shipment.shipment_items.iter().find(|item| item.response_shipment_sku_quantity < 0) {
+        return Err(ValidationError::NegativeQuantity {
+          shipment: index,
+          merchant_sku: item.merchant_sku.to_string(),
+        });
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Builds a `ShipOrder`, see `ShipOrder::builder`
+///
+/// ```
+/// use jet::orders::ShipOrder;
+///
+/// let ship = ShipOrder::builder()
+///   .alt_order_id("1001")
+///   .shipment(|s| s.carrier("UPS").tracking("1Z12342452342").item("test_product", 1, 30))
+///   .build();
+/// assert_eq!(ship.shipments[0].shipment_items.len(), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct ShipOrderBuilder {
+  alt_order_id: Option<String>,
+  shipments: Vec<ShipOrderShipment>,
+}
+
+impl ShipOrderBuilder {
+  pub fn alt_order_id<T: Into<String>>(mut self, alt_order_id: T) -> Self {
+    self.alt_order_id = Some(alt_order_id.into());
+    self
+  }
+
+  /// Adds a shipment configured by `f`
+  pub fn shipment<F>(mut self, f: F) -> Self
+  where
+    F: FnOnce(ShipOrderShipmentBuilder) -> ShipOrderShipmentBuilder,
+  {
+    self.shipments.push(f(ShipOrderShipmentBuilder::new()).build());
+    self
+  }
+
+  pub fn build(self) -> ShipOrder {
+    ShipOrder {
+      alt_order_id: self.alt_order_id,
+      shipments: self.shipments,
+    }
+  }
+}
+
+/// Builds a `ShipOrderShipment`. The shipment date defaults to now.
+#[derive(Debug)]
+pub struct ShipOrderShipmentBuilder {
+  shipment: ShipOrderShipment,
+}
+
+impl ShipOrderShipmentBuilder {
+  fn new() -> Self {
+    ShipOrderShipmentBuilder {
+      shipment: ShipOrderShipment {
+        carrier: Carrier::Other(String::new()),
+        shipment_tracking_number: None,
+        shipment_items: vec![],
+        response_shipment_date: Utc::now(),
+      },
+    }
+  }
+
+  pub fn carrier<T: Into<Carrier>>(mut self, carrier: T) -> Self {
+    self.shipment.carrier = carrier.into();
+    self
+  }
+
+  pub fn tracking<T: Into<String>>(mut self, tracking_number: T) -> Self {
+    self.shipment.shipment_tracking_number = Some(tracking_number.into());
+    self
+  }
+
+  pub fn shipment_date(mut self, date: DateTime<Utc>) -> Self {
+    self.shipment.response_shipment_date = date;
+    self
+  }
+
+  pub fn item<T: Into<MerchantSkuId>>(mut self, merchant_sku: T, quantity: i32, days_to_return: i32) -> Self {
+    self.shipment.shipment_items.push(ShipOrderShipmentItem {
+      merchant_sku: merchant_sku.into(),
+      response_shipment_sku_quantity: quantity,
+      days_to_return,
+      return_location: None,
+      rma_number: None,
+    });
+    self
+  }
+
+  pub fn build(self) -> ShipOrderShipment {
+    self.shipment
+  }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CancelOrderItem {
+  pub merchant_sku: MerchantSkuId,
+  /// Number of units of the SKU being cancelled
+  pub response_shipment_cancel_qty: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CancelOrder {
+  pub alt_order_id: Option<String>,
+  pub order_items: Vec<CancelOrderItem>,
+}
+
+/// Lazily downloads order details, one request per `next` call.
+/// Returned by `Client::orders_for_status`.
+pub struct OrderDetails<'a> {
+  client: &'a Client,
+  urls: std::vec::IntoIter<String>,
+}
+
+impl<'a> Iterator for OrderDetails<'a> {
+  type Item = Result<Order>;
+
+  fn next(&mut self) -> Option<Result<Order>> {
+    let url = self.urls.next()?;
+    Some(self.client.get_order_detail(&url))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.urls.size_hint()
+  }
+}
+
+impl Client {
+  pub fn get_orders(&self, status: OrderStatus) -> Result<GetOrdersResponse> {
+    self.get_orders_with(status, GetOrdersOptions::default())
+  }
+
+  pub fn get_orders_with(&self, status: OrderStatus, opts: GetOrdersOptions) -> Result<GetOrdersResponse> {
+    self.request(
+      Method::GET,
+      &status.orders_path()?,
+      |req| {
+        req.query(&opts)
+      },
+    )
+  }
+
+  /// Lists orders Jet has archived, which no status listing returns. The
+  /// URLs are used with `get_order_detail` like any other.
+  pub fn get_archived_orders(&self) -> Result<GetOrdersResponse> {
+    self.request(Method::GET, "/orders/archived", std::convert::identity)
+  }
+
+  pub fn get_order_detail(&self, order_url: &str) -> Result<Order> {
+    self.request(Method::GET, order_url, std::convert::identity)
+  }
+
+  /// Same as `get_order_detail` but also returns the response status and
+  /// headers
+  pub fn get_order_detail_full(&self, order_url: &str) -> Result<ApiResponse<Order>> {
+    self.request_full(Method::GET, order_url, std::convert::identity)
+  }
+
+  /// Same as `get_order_detail` but returns the untyped JSON, useful for
+  /// fields the crate doesn't model or debugging deserialization failures
+  pub fn get_order_detail_raw(&self, order_url: &str) -> Result<serde_json::Value> {
+    self.request(Method::GET, order_url, std::convert::identity)
+  }
+
+  /// Looks up an order by its alt_order_id. Jet associates the alt_order_id
+  /// with the order when it is sent in `acknowledge_order`, so this only
+  /// finds orders that were acknowledged with one.
+  pub fn get_order_detail_by_alt_id(&self, alt_order_id: &str) -> Result<Order> {
+    self.request(Method::GET, &alt_order_url(alt_order_id), std::convert::identity)
+  }
+
+  /// Calls `f` with each order URL in `status` as the response is read,
+  /// without holding the whole list in memory. Follows `next_page_url`
+  /// like `get_all_order_urls`. Not available on `AsyncClient`.
+  pub fn stream_order_urls<F: FnMut(String)>(&self, status: OrderStatus, mut f: F) -> Result<()> {
+    let mut path = status.orders_path()?;
+    let mut seen = vec![];
+    loop {
+      let res = self.send(Method::GET, &path, std::convert::identity)?;
+      if is_html(res.headers()) {
+        let status = res.status();
+        return Err(Error::Request { path, status, body: res.text()? });
+      }
+      let mut de = serde_json::Deserializer::from_reader(BufReader::new(res));
+      let next = OrderUrlsSeed(&mut f).deserialize(&mut de)?;
+      de.end()?;
+      match next {
+        // stop if Jet hands out a page twice instead of looping forever
+        Some(next) if !seen.contains(&next) => {
+          seen.push(next.clone());
+          path = next;
+        }
+        _ => return Ok(()),
+      }
+    }
+  }
+
+  /// All order URLs in `status`, following `next_page_url` until Jet
+  /// returns the last page
+  pub fn get_all_order_urls(&self, status: OrderStatus) -> Result<Vec<String>> {
+    let mut res = self.get_orders(status)?;
+    let mut urls = std::mem::take(&mut res.order_urls);
+    let mut seen = vec![];
+    while let Some(next) = res.next_page_url.take() {
+      // stop if Jet hands out a page twice instead of looping forever
+      if seen.contains(&next) {
+        break;
+      }
+      res = self.request(Method::GET, &next, std::convert::identity)?;
+      urls.append(&mut res.order_urls);
+      seen.push(next);
+    }
+    Ok(urls)
+  }
+
+  /// Number of orders in each of `OrderStatus::KNOWN`, listing the
+  /// statuses in parallel. Fails if listing any status fails.
+  pub fn get_order_counts(&self) -> Result<HashMap<OrderStatus, usize>> {
+    let counts = parallel_map(&OrderStatus::KNOWN, OrderStatus::KNOWN.len(), |status| {
+      self.get_all_order_urls(status.clone()).map(|urls| (status.clone(), urls.len()))
+    });
+    counts.into_iter().collect()
+  }
+
+  /// Lists the orders in `status`, following `next_page_url` like
+  /// `get_all_order_urls`, and returns an iterator downloading each order's
+  /// details. Failed downloads are yielded as errors without stopping the
+  /// iteration.
+  pub fn orders_for_status(&self, status: OrderStatus) -> Result<OrderDetails<'_>> {
+    let urls = self.get_all_order_urls(status)?;
+    Ok(OrderDetails {
+      client: self,
+      urls: urls.into_iter(),
+    })
+  }
+
+  /// Downloads the details of `urls` on up to `concurrency` threads.
+  /// Results are in the same order as `urls`, a failed download doesn't
+  /// stop the others.
+  pub fn get_orders_details_parallel(&self, urls: &[String], concurrency: usize) -> Vec<Result<Order>> {
+    parallel_map(urls, concurrency, |url| self.get_order_detail(url))
+  }
+
+  pub fn acknowledge_order(&self, order_id: &MerchantOrderId, ack: &AcknowledgeOrder) -> Result<()> {
+    self.request_deduped(Method::PUT, &format!("/orders/{}/acknowledge", order_id), ack)
+  }
+
+  /// Rejects the whole order, see `AcknowledgeOrder::reject`
+  pub fn reject_order(&self, order: &Order, reason: RejectReason) -> Result<()> {
+    self.acknowledge_order(&order.merchant_order_id, &AcknowledgeOrder::reject(order, reason))
+  }
+
+  pub fn ship_order(&self, order_id: &MerchantOrderId, ship: &ShipOrder) -> Result<()> {
+    ship.validate()?;
+    self.request_deduped(Method::PUT, &format!("/orders/{}/shipped", order_id), ship)
+  }
+
+  /// Acknowledges the order, then ships it. These are two requests, so if
+  /// shipping fails the order stays acknowledged, `Error::Fulfill` tells
+  /// which step failed. The shipment is validated before acknowledging.
+  pub fn fulfill_order(&self, order_id: &MerchantOrderId, ack: &AcknowledgeOrder, ship: &ShipOrder) -> Result<()> {
+    let fail = |step| move |err| Error::Fulfill { step, source: Box::new(err) };
+    ship
+      .validate()
+      .map_err(|err| fail(FulfillStep::Ship)(err.into()))?;
+    self
+      .acknowledge_order(order_id, ack)
+      .map_err(fail(FulfillStep::Acknowledge))?;
+    self
+      .ship_order(order_id, ship)
+      .map_err(fail(FulfillStep::Ship))
+  }
+
+  pub fn cancel_order(&self, order_id: &MerchantOrderId, cancel: &CancelOrder) -> Result<()> {
+    self.request_deduped(Method::PUT, &format!("/orders/{}/cancel", order_id), cancel)
+  }
+
+  /// Downloads the sub-orders an order was split into, see `Order::sub_orders`
+  pub fn get_sub_orders(&self, order_id: &MerchantOrderId) -> Result<Vec<Order>> {
+    let order = self.get_order_detail(&order_url(order_id.as_str()))?;
+    order
+      .sub_orders
+      .unwrap_or_default()
+      .iter()
+      .map(|sub_order_id| self.get_order_detail(&order_url(sub_order_id)))
+      .collect()
+  }
+
+  /// Same as `acknowledge_order` but only returns the request it would send
+  pub fn preview_acknowledge_order(&self, order_id: &MerchantOrderId, ack: &AcknowledgeOrder) -> Result<PreparedRequest> {
+    self.prepare(
+      Method::PUT,
+      &format!("/orders/{}/acknowledge", order_id),
+      |req| {
+        req.json(ack)
+      },
+    )
+  }
+
+  /// Same as `ship_order` but only returns the request it would send
+  pub fn preview_ship_order(&self, order_id: &MerchantOrderId, ship: &ShipOrder) -> Result<PreparedRequest> {
+    ship.validate()?;
+    self.prepare(
+      Method::PUT,
+      &format!("/orders/{}/shipped", order_id),
+      |req| {
+        req.json(ship)
+      },
+    )
+  }
+}
+
+#[cfg(feature = "async")]
+impl AsyncClient {
+  pub async fn get_orders(&self, status: OrderStatus) -> Result<GetOrdersResponse> {
+    self.get_orders_with(status, GetOrdersOptions::default()).await
+  }
+
+  pub async fn get_orders_with(&self, status: OrderStatus, opts: GetOrdersOptions) -> Result<GetOrdersResponse> {
+    self.request(
+      Method::GET,
+      &status.orders_path()?,
+      |req| {
+        req.query(&opts)
+      },
+    ).await
+  }
+
+  /// Lists orders Jet has archived, which no status listing returns. The
+  /// URLs are used with `get_order_detail` like any other.
+  pub async fn get_archived_orders(&self) -> Result<GetOrdersResponse> {
+    self.request(Method::GET, "/orders/archived", std::convert::identity).await
+  }
+
+  pub async fn get_order_detail(&self, order_url: &str) -> Result<Order> {
+    self.request(Method::GET, order_url, std::convert::identity).await
+  }
+
+  /// Same as `get_order_detail` but also returns the response status and
+  /// headers
+  pub async fn get_order_detail_full(&self, order_url: &str) -> Result<ApiResponse<Order>> {
+    self.request_full(Method::GET, order_url, std::convert::identity).await
+  }
+
+  /// Same as `get_order_detail` but returns the untyped JSON, useful for
+  /// fields the crate doesn't model or debugging deserialization failures
+  pub async fn get_order_detail_raw(&self, order_url: &str) -> Result<serde_json::Value> {
+    self.request(Method::GET, order_url, std::convert::identity).await
+  }
+
+  /// Looks up an order by its alt_order_id. Jet associates the alt_order_id
+  /// with the order when it is sent in `acknowledge_order`, so this only
+  /// finds orders that were acknowledged with one.
+  pub async fn get_order_detail_by_alt_id(&self, alt_order_id: &str) -> Result<Order> {
+    self.request(Method::GET, &alt_order_url(alt_order_id), std::convert::identity).await
+  }
+
+  /// All order URLs in `status`, following `next_page_url` until Jet
+  /// returns the last page
+  pub async fn get_all_order_urls(&self, status: OrderStatus) -> Result<Vec<String>> {
+    let mut res = self.get_orders(status).await?;
+    let mut urls = std::mem::take(&mut res.order_urls);
+    let mut seen = vec![];
+    while let Some(next) = res.next_page_url.take() {
+      // stop if Jet hands out a page twice instead of looping forever
+      if seen.contains(&next) {
+        break;
+      }
+      res = self.request(Method::GET, &next, std::convert::identity).await?;
+      urls.append(&mut res.order_urls);
+      seen.push(next);
+    }
+    Ok(urls)
+  }
+
+  /// Number of orders in each of `OrderStatus::KNOWN`, listing the
+  /// statuses in parallel. Fails if listing any status fails.
+  pub async fn get_order_counts(&self) -> Result<HashMap<OrderStatus, usize>> {
+    let counts: Vec<Result<(OrderStatus, usize)>> = stream::iter(&OrderStatus::KNOWN)
+      .map(|status| async move {
+        self.get_all_order_urls(status.clone()).await.map(|urls| (status.clone(), urls.len()))
+      })
+      .buffered(OrderStatus::KNOWN.len())
+      .collect()
+      .await;
+    counts.into_iter().collect()
+  }
+
+  /// Lists the orders in `status`, following `next_page_url` like
+  /// `get_all_order_urls`, and returns a stream downloading each order's
+  /// details. Failed downloads are yielded as errors without ending the
+  /// stream.
+  pub async fn orders_for_status(
+    &self,
+    status: OrderStatus,
+  ) -> Result<impl Stream<Item = Result<Order>> + '_> {
+    let urls = self.get_all_order_urls(status).await?;
+    Ok(stream::iter(urls).then(move |url| async move {
+      self.get_order_detail(&url).await
+    }))
+  }
+
+  /// Downloads the details of `urls` with up to `concurrency` requests in
+  /// flight. Results are in the same order as `urls`, a failed download
+  /// doesn't stop the others.
+  pub async fn get_orders_details_parallel(&self, urls: &[String], concurrency: usize) -> Vec<Result<Order>> {
+    stream::iter(urls)
+      .map(|url| self.get_order_detail(url))
+      .buffered(concurrency.max(1))
+      .collect()
+      .await
+  }
+
+  pub async fn acknowledge_order(&self, order_id: &MerchantOrderId, ack: &AcknowledgeOrder) -> Result<()> {
+    self.request_deduped(Method::PUT, &format!("/orders/{}/acknowledge", order_id), ack).await
+  }
+
+  /// Rejects the whole order, see `AcknowledgeOrder::reject`
+  pub async fn reject_order(&self, order: &Order, reason: RejectReason) -> Result<()> {
+    self.acknowledge_order(&order.merchant_order_id, &AcknowledgeOrder::reject(order, reason)).await
+  }
+
+  pub async fn ship_order(&self, order_id: &MerchantOrderId, ship: &ShipOrder) -> Result<()> {
+    ship.validate()?;
+    self.request_deduped(Method::PUT, &format!("/orders/{}/shipped", order_id), ship).await
+  }
+
+  /// Acknowledges the order, then ships it. These are two requests, so if
+  /// shipping fails the order stays acknowledged, `Error::Fulfill` tells
+  /// which step failed. The shipment is validated before acknowledging.
+  pub async fn fulfill_order(&self, order_id: &MerchantOrderId, ack: &AcknowledgeOrder, ship: &ShipOrder) -> Result<()> {
+    let fail = |step| move |err| Error::Fulfill { step, source: Box::new(err) };
+    ship
+      .validate()
+      .map_err(|err| fail(FulfillStep::Ship)(err.into()))?;
+    self
+      .acknowledge_order(order_id, ack).await
+      .map_err(fail(FulfillStep::Acknowledge))?;
+    self
+      .ship_order(order_id, ship).await
+      .map_err(fail(FulfillStep::Ship))
+  }
+
+  pub async fn cancel_order(&self, order_id: &MerchantOrderId, cancel: &CancelOrder) -> Result<()> {
+    self.request_deduped(Method::PUT, &format!("/orders/{}/cancel", order_id), cancel).await
+  }
+
+  /// Downloads the sub-orders an order was split into, see `Order::sub_orders`
+  pub async fn get_sub_orders(&self, order_id: &MerchantOrderId) -> Result<Vec<Order>> {
+    let order = self.get_order_detail(&order_url(order_id.as_str())).await?;
+    let mut sub_orders = vec![];
+    for sub_order_id in order.sub_orders.unwrap_or_default() {
+      sub_orders.push(self.get_order_detail(&order_url(&sub_order_id)).await?);
+    }
+    Ok(sub_orders)
+  }
+
+  /// Same as `acknowledge_order` but only returns the request it would send
+  pub fn preview_acknowledge_order(&self, order_id: &MerchantOrderId, ack: &AcknowledgeOrder) -> Result<PreparedRequest> {
+    self.prepare(
+      Method::PUT,
+      &format!("/orders/{}/acknowledge", order_id),
+      |req| {
+        req.json(ack)
+      },
+    )
+  }
+
+  /// Same as `ship_order` but only returns the request it would send
+  pub fn preview_ship_order(&self, order_id: &MerchantOrderId, ship: &ShipOrder) -> Result<PreparedRequest> {
+    ship.validate()?;
+    self.prepare(
+      Method::PUT,
+      &format!("/orders/{}/shipped", order_id),
+      |req| {
+        req.json(ship)
+      },
+    )
+  }
+}
+
+#[test]
+fn test_get_orders() {
+  use crate::client::get_test_client;
+  let client = get_test_client();
+  println!("{:#?}", client.get_orders(OrderStatus::Ready).unwrap());
+}
+
+#[test]
+fn test_get_order_detail() {
+  use crate::client::get_test_client;
+  let client = get_test_client();
+  println!(
+    "{:#?}",
+    client
+      .get_order_detail("/orders/withoutShipmentDetail/2ab4c8b414124f0fa04072d615ec0610")
+      .unwrap()
+  );
+}
+
+#[test]
+fn test_acknowledge_order() {
+  use crate::client::get_test_client;
+  let client = get_test_client();
   println!(
     "{:#?}",
     client
       .acknowledge_order(
-        "2ab4c8b414124f0fa04072d615ec0610",
+        &"2ab4c8b414124f0fa04072d615ec0610".into(),
         &AcknowledgeOrder {
-          acknowledgement_status: "accepted",
+          acknowledgement_status: AcknowledgementStatus::Accepted,
           alt_order_id: None,
           order_items: vec![AcknowledgeOrderItem {
-            order_item_acknowledgement_status: "fulfillable",
-            order_item_id: "2906d22b212d4745ab9986b80b1ad2af".to_owned(),
+            order_item_acknowledgement_status: ItemAcknowledgementStatus::Fulfillable,
+            order_item_id: "2906d22b212d4745ab9986b80b1ad2af".into(),
             alt_order_item_id: None,
           }],
         }
@@ -331,27 +1854,1155 @@ fn test_acknowledge_order() {
 }
 
 #[test]
-fn test_ship_order() {
-  use crate::client::get_test_client;
-  let client = get_test_client();
+fn test_ship_order() {
+  use crate::client::get_test_client;
+  let client = get_test_client();
+  client
+    .ship_order(
+      &"2ab4c8b414124f0fa04072d615ec0610".into(),
+      &ShipOrder {
+        alt_order_id: None,
+        shipments: vec![ShipOrderShipment {
+          carrier: Carrier::Ups,
+          shipment_tracking_number: Some("1Z12342452342".to_owned()),
+          shipment_items: vec![ShipOrderShipmentItem {
+            merchant_sku: "test_product".into(),
+            response_shipment_sku_quantity: 1,
+            days_to_return: 30,
+            return_location: None,
+            rma_number: None,
+          }],
+          response_shipment_date: Utc::now(),
+        }],
+      },
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_cancel_order() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+  use mockito::Matcher;
+  use serde_json::json;
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let cancel_mock = server
+    .mock("PUT", "/orders/2ab4c8b414124f0fa04072d615ec0610/cancel")
+    .match_body(Matcher::Json(json!({
+      "alt_order_id": null,
+      "order_items": [{ "merchant_sku": "test_product", "response_shipment_cancel_qty": 1 }]
+    })))
+    .create();
+
+  get_mock_client(&server)
+    .cancel_order(
+      &"2ab4c8b414124f0fa04072d615ec0610".into(),
+      &CancelOrder {
+        alt_order_id: None,
+        order_items: vec![CancelOrderItem {
+          merchant_sku: "test_product".into(),
+          response_shipment_cancel_qty: 1,
+        }],
+      },
+    )
+    .unwrap();
+
+  cancel_mock.assert();
+}
+
+#[test]
+fn test_get_order_detail_mock() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let order_mock = server
+    .mock("GET", "/orders/withoutShipmentDetail/2ab4c8b414124f0fa04072d615ec0610")
+    .match_header("authorization", "Bearer mock-token")
+    .with_body(include_str!("../test_data/order.json"))
+    .create();
+
+  let order = get_mock_client(&server)
+    .get_order_detail("/orders/withoutShipmentDetail/2ab4c8b414124f0fa04072d615ec0610")
+    .unwrap();
+
+  order_mock.assert();
+  assert_eq!(order.order_items.len(), 2);
+  assert_eq!(order.status, OrderStatus::Inprogress);
+}
+
+#[test]
+fn test_acknowledge_order_mock() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+  use mockito::Matcher;
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let ack_mock = server
+    .mock("PUT", "/orders/2ab4c8b414124f0fa04072d615ec0610/acknowledge")
+    .match_header("content-type", "application/json")
+    .match_body(Matcher::JsonString(include_str!("../test_data/acknowledge_order.json").to_owned()))
+    .with_status(204)
+    .create();
+
+  get_mock_client(&server)
+    .acknowledge_order(
+      &"2ab4c8b414124f0fa04072d615ec0610".into(),
+      &AcknowledgeOrder {
+        acknowledgement_status: AcknowledgementStatus::Accepted,
+        alt_order_id: Some("PO-1001".to_owned()),
+        order_items: vec![
+          AcknowledgeOrderItem {
+            order_item_acknowledgement_status: ItemAcknowledgementStatus::Fulfillable,
+            order_item_id: "2906d22b212d4745ab9986b80b1ad2af".into(),
+            alt_order_item_id: None,
+          },
+          AcknowledgeOrderItem {
+            order_item_acknowledgement_status: ItemAcknowledgementStatus::NonfulfillableNoInventory,
+            order_item_id: "4c1a0b3e77f34b6e9d5e2f8a1b2c3d4e".into(),
+            alt_order_item_id: None,
+          },
+        ],
+      },
+    )
+    .unwrap();
+
+  ack_mock.assert();
+}
+
+#[test]
+fn test_ship_order_mock() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+  use chrono::TimeZone;
+  use mockito::Matcher;
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let ship_mock = server
+    .mock("PUT", "/orders/2ab4c8b414124f0fa04072d615ec0610/shipped")
+    .match_body(Matcher::JsonString(include_str!("../test_data/ship_order.json").to_owned()))
+    .with_status(204)
+    .create();
+
+  get_mock_client(&server)
+    .ship_order(
+      &"2ab4c8b414124f0fa04072d615ec0610".into(),
+      &ShipOrder {
+        alt_order_id: None,
+        shipments: vec![ShipOrderShipment {
+          carrier: Carrier::Ups,
+          shipment_tracking_number: Some("1Z12342452342".to_owned()),
+          shipment_items: vec![ShipOrderShipmentItem {
+            merchant_sku: "test_product".into(),
+            response_shipment_sku_quantity: 2,
+            days_to_return: 30,
+            return_location: None,
+            rma_number: None,
+          }],
+          response_shipment_date: Utc.with_ymd_and_hms(2017, 3, 15, 9, 0, 0).unwrap(),
+        }],
+      },
+    )
+    .unwrap();
+
+  ship_mock.assert();
+}
+
+#[test]
+fn test_get_order_counts() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", mockito::Matcher::Regex("^/orders/(created|acknowledged|inprogress|complete)$".to_owned()))
+    .with_body(r#"{"order_urls":[]}"#)
+    .expect(4)
+    .create();
+  server
+    .mock("GET", "/orders/ready")
+    .with_body(r#"{"order_urls":["/orders/withoutShipmentDetail/a1","/orders/withoutShipmentDetail/a2"]}"#)
+    .create();
+
+  let counts = get_mock_client(&server).get_order_counts().unwrap();
+  assert_eq!(counts.len(), 5);
+  assert_eq!(counts[&OrderStatus::Ready], 2);
+  assert_eq!(counts[&OrderStatus::Complete], 0);
+}
+
+#[test]
+fn test_get_orders_details_parallel() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", mockito::Matcher::Regex("^/orders/withoutShipmentDetail/a".to_owned()))
+    .with_body(include_str!("../test_data/order.json"))
+    .expect(4)
+    .create();
+  server
+    .mock("GET", "/orders/withoutShipmentDetail/b1")
+    .with_status(404)
+    .create();
+
+  let urls: Vec<String> = ["a1", "b1", "a2", "a3", "a4"]
+    .iter()
+    .map(|id| format!("/orders/withoutShipmentDetail/{}", id))
+    .collect();
+  let results = get_mock_client(&server).get_orders_details_parallel(&urls, 3);
+
+  assert_eq!(results.len(), 5);
+  assert!(matches!(results[1], Err(Error::NotFound { .. })));
+  for index in [0, 2, 3, 4] {
+    assert!(results[index].is_ok());
+  }
+  assert!(get_mock_client(&server).get_orders_details_parallel(&[], 3).is_empty());
+}
+
+#[test]
+fn test_get_order_detail_by_alt_id() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let order_mock = server
+    .mock("GET", "/orders/alt_order_id/PO%201001")
+    .with_body(include_str!("../test_data/order.json"))
+    .create();
+
+  let order = get_mock_client(&server).get_order_detail_by_alt_id("PO 1001").unwrap();
+
+  order_mock.assert();
+  assert_eq!(order.merchant_order_id, "2ab4c8b414124f0fa04072d615ec0610");
+}
+
+#[test]
+fn test_get_order_detail_full() {
+  use crate::client::{get_mock_client, StatusCode, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", "/orders/withoutShipmentDetail/2ab4c8b414124f0fa04072d615ec0610")
+    .with_header("x-ratelimit-remaining", "42")
+    .with_body(include_str!("../test_data/order.json"))
+    .create();
+
+  let res = get_mock_client(&server)
+    .get_order_detail_full("/orders/withoutShipmentDetail/2ab4c8b414124f0fa04072d615ec0610")
+    .unwrap();
+
+  assert_eq!(res.status, StatusCode::OK);
+  assert_eq!(res.header("x-ratelimit-remaining"), Some("42"));
+  assert_eq!(res.value.merchant_order_id, "2ab4c8b414124f0fa04072d615ec0610");
+}
+
+#[test]
+fn test_preview_ship_order() {
+  use crate::client::{get_mock_client, Method};
+
+  let server = mockito::Server::new();
+  let ship = ShipOrder::builder()
+    .shipment(|s| s.carrier("UPS").tracking("1Z12342452342").item("test_product", 1, 30))
+    .build();
+  let preview = get_mock_client(&server)
+    .preview_ship_order(&"2ab4c8b414124f0fa04072d615ec0610".into(), &ship)
+    .unwrap();
+
+  assert_eq!(preview.method, Method::PUT);
+  assert_eq!(
+    preview.url,
+    format!("{}/orders/2ab4c8b414124f0fa04072d615ec0610/shipped", server.url())
+  );
+  assert_eq!(preview.headers["authorization"], "Bearer <redacted>");
+  assert_eq!(preview.headers["content-type"], "application/json");
+  let body: serde_json::Value = serde_json::from_str(preview.body.as_deref().unwrap()).unwrap();
+  assert_eq!(body["shipments"][0]["shipment_tracking_number"], "1Z12342452342");
+}
+
+#[test]
+fn test_fulfill_order() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let ack_mock = server
+    .mock("PUT", "/orders/2ab4c8b414124f0fa04072d615ec0610/acknowledge")
+    .with_status(204)
+    .create();
+  let ship_mock = server
+    .mock("PUT", "/orders/2ab4c8b414124f0fa04072d615ec0610/shipped")
+    .with_status(400)
+    .with_body(r#"{"errors":["invalid merchant_sku"]}"#)
+    .create();
+
+  let ack = AcknowledgeOrder {
+    acknowledgement_status: AcknowledgementStatus::Accepted,
+    alt_order_id: None,
+    order_items: vec![],
+  };
+  let ship = ShipOrder::builder()
+    .shipment(|s| s.carrier("UPS").item("test_product", 1, 30))
+    .build();
+  let err = get_mock_client(&server)
+    .fulfill_order(&"2ab4c8b414124f0fa04072d615ec0610".into(), &ack, &ship)
+    .unwrap_err();
+
+  ack_mock.assert();
+  ship_mock.assert();
+  match err {
+    Error::Fulfill { step, ref source } => {
+      assert_eq!(step, FulfillStep::Ship);
+      assert!(matches!(**source, Error::JetApi { .. }));
+    }
+    other => panic!("unexpected error: {}", other),
+  }
+  assert_eq!(err.status_code(), Some(reqwest::StatusCode::BAD_REQUEST));
+}
+
+#[test]
+fn test_dedupe_ship_order() {
+  use crate::client::{Client, ClientOptions, MOCK_TOKEN};
+  use std::time::Duration;
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let ship_mock = server
+    .mock("PUT", mockito::Matcher::Regex("^/orders/[a-z0-9]+/shipped$".to_owned()))
+    .with_status(204)
+    .expect(2)
+    .create();
+
+  let client = Client::new(ClientOptions {
+    api_user: "mock-user".to_owned(),
+    secret: "mock-secret".to_owned(),
+    endpoint: Some(server.url()),
+    dedupe_window: Some(Duration::from_secs(60)),
+    ..Default::default()
+  })
+  .unwrap();
+  let ship = ShipOrder::builder()
+    .shipment(|s| s.carrier("UPS").item("test_product", 1, 30))
+    .build();
+  client.ship_order(&"aa01".into(), &ship).unwrap();
+  client.ship_order(&"aa01".into(), &ship).unwrap();
+  client.ship_order(&"aa02".into(), &ship).unwrap();
+
+  ship_mock.assert();
+}
+
+#[test]
+fn test_get_archived_orders() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let archived_mock = server
+    .mock("GET", "/orders/archived")
+    .with_body(r#"{"order_urls":["/orders/withoutShipmentDetail/aa01"]}"#)
+    .create();
+
+  let res = get_mock_client(&server).get_archived_orders().unwrap();
+
+  archived_mock.assert();
+  assert_eq!(res.order_urls, ["/orders/withoutShipmentDetail/aa01"]);
+}
+
+#[test]
+fn test_ship_item_return_routing() {
+  let mut item = ShipOrder::builder()
+    .shipment(|s| s.carrier("UPS").item("test_product", 1, 30))
+    .build()
+    .shipments
+    .remove(0)
+    .shipment_items
+    .remove(0);
+  let value = serde_json::to_value(&item).unwrap();
+  assert!(value.get("return_location").is_none() && value.get("RMA_number").is_none());
+
+  item.return_location = Some(get_test_order().shipping_to.address);
+  item.rma_number = Some("RMA-1001".to_owned());
+  let value = serde_json::to_value(&item).unwrap();
+  assert_eq!(value["RMA_number"], "RMA-1001");
+  assert!(value["return_location"]["zip_code"].is_string());
+}
+
+#[test]
+fn test_get_all_order_urls() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", "/orders/ready")
+    .with_body(r#"{"order_urls":["/orders/withoutShipmentDetail/aa01"],"next_page_url":"/orders/ready?page=2"}"#)
+    .create();
+  server
+    .mock("GET", "/orders/ready?page=2")
+    .with_body(r#"{"order_urls":["/orders/withoutShipmentDetail/aa02"],"next_page_url":"/orders/ready?page=3"}"#)
+    .create();
+  server
+    .mock("GET", "/orders/ready?page=3")
+    .with_body(r#"{"order_urls":["/orders/withoutShipmentDetail/aa03"]}"#)
+    .create();
+
+  let urls = get_mock_client(&server).get_all_order_urls(OrderStatus::Ready).unwrap();
+  assert_eq!(
+    urls,
+    [
+      "/orders/withoutShipmentDetail/aa01",
+      "/orders/withoutShipmentDetail/aa02",
+      "/orders/withoutShipmentDetail/aa03",
+    ]
+  );
+}
+
+#[test]
+fn test_stream_order_urls() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let urls: Vec<String> = (0..1000).map(|i| format!("/orders/withoutShipmentDetail/{:04x}", i)).collect();
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", "/orders/ready")
+    .with_body(serde_json::json!({ "order_urls": urls, "other": { "nested": [1, 2] } }).to_string())
+    .create();
+
+  let mut streamed = vec![];
+  get_mock_client(&server)
+    .stream_order_urls(OrderStatus::Ready, |url| streamed.push(url))
+    .unwrap();
+  assert_eq!(streamed, urls);
+}
+
+#[test]
+fn test_stream_order_urls_pages_and_html() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", "/orders/ready")
+    .with_body(r#"{"next_page_url":"/orders/ready?page=2","order_urls":["/orders/withoutShipmentDetail/aa01"]}"#)
+    .create();
+  server
+    .mock("GET", "/orders/ready?page=2")
+    .with_body(r#"{"order_urls":["/orders/withoutShipmentDetail/bb02"],"next_page_url":"/orders/ready?page=2"}"#)
+    .create();
+  server
+    .mock("GET", "/orders/acknowledged")
+    .with_header("content-type", "text/html; charset=utf-8")
+    .with_body("<html><body>Down for maintenance</body></html>")
+    .create();
+
+  let client = get_mock_client(&server);
+  let mut streamed = vec![];
   client
-    .ship_order(
-      "2ab4c8b414124f0fa04072d615ec0610",
-      &ShipOrder {
-        alt_order_id: None,
-        shipments: vec![ShipOrderShipment {
-          carrier: "UPS".to_owned(),
-          shipment_tracking_number: Some("1Z12342452342".to_owned()),
-          shipment_items: vec![ShipOrderShipmentItem {
-            merchant_sku: "test_product".to_owned(),
-            response_shipment_sku_quantity: 1,
-            days_to_return: 30,
-          }],
-          response_shipment_date: Utc::now(),
-        }],
+    .stream_order_urls(OrderStatus::Ready, |url| streamed.push(url))
+    .unwrap();
+  assert_eq!(streamed, ["/orders/withoutShipmentDetail/aa01", "/orders/withoutShipmentDetail/bb02"]);
+
+  let err = client.stream_order_urls(OrderStatus::Acknowledged, |_| ()).unwrap_err();
+  match err {
+    Error::Request { ref path, ref body, .. } => {
+      assert_eq!(path, "/orders/acknowledged");
+      assert!(body.contains("maintenance"));
+    }
+    other => panic!("unexpected error: {:?}", other),
+  }
+}
+
+#[test]
+fn test_order_status_classification() {
+  let cases = [
+    // (status, should_fulfill, needs_acknowledgement, is_actionable, is_terminal)
+    (OrderStatus::Created, false, false, false, false),
+    (OrderStatus::Ready, true, true, true, false),
+    (OrderStatus::Acknowledged, false, false, true, false),
+    (OrderStatus::Inprogress, false, false, true, false),
+    (OrderStatus::Complete, false, false, false, true),
+    (OrderStatus::Unknown("on hold".to_owned()), false, false, false, false),
+  ];
+  for (status, fulfill, acknowledge, actionable, terminal) in cases {
+    assert_eq!(status.should_fulfill(), fulfill, "{}", status);
+    assert_eq!(status.needs_acknowledgement(), acknowledge, "{}", status);
+    assert_eq!(status.is_actionable(), actionable, "{}", status);
+    assert_eq!(status.is_terminal(), terminal, "{}", status);
+  }
+
+  let mut order = get_test_order();
+  assert!(order.is_actionable() && !order.is_terminal() && !order.needs_acknowledgement());
+  order.status = OrderStatus::Ready;
+  assert!(order.should_fulfill());
+  assert!(!order.needs_acknowledgement());
+  order.acknowledgement_status = None;
+  assert!(order.needs_acknowledgement());
+}
+
+#[test]
+fn test_order_summary() {
+  assert_eq!(
+    get_test_order().summary(),
+    "order 2ab4c8b414124f0fa04072d615ec0610 (401234567890): inprogress, 3 units, total 63.94, ship by 2017-03-15T12:34:56Z"
+  );
+}
+
+#[test]
+fn test_acknowledgement_status_serde() {
+  assert_eq!(
+    serde_json::to_string(&AcknowledgementStatus::RejectedItemLevelError).unwrap(),
+    r#""rejected - item level error""#
+  );
+  assert_eq!(
+    serde_json::from_str::<AcknowledgementStatus>(r#""accepted""#).unwrap(),
+    AcknowledgementStatus::Accepted
+  );
+  assert_eq!(
+    serde_json::to_string(&ItemAcknowledgementStatus::NonfulfillableInvalidMerchantSku).unwrap(),
+    r#""nonfulfillable - invalid merchant SKU""#
+  );
+  assert_eq!(
+    serde_json::from_str::<AcknowledgementStatus>(r#""acepted""#).unwrap(),
+    AcknowledgementStatus::Other("acepted".to_owned())
+  );
+  assert_eq!(
+    serde_json::to_string(&ItemAcknowledgementStatus::Other("nonfulfillable - recalled".to_owned())).unwrap(),
+    r#""nonfulfillable - recalled""#
+  );
+
+  let order = get_test_order();
+  assert_eq!(order.acknowledgement_status, Some(AcknowledgementStatus::Accepted));
+  assert_eq!(
+    order.order_items[0].order_item_acknowledgement_status,
+    Some(ItemAcknowledgementStatus::Fulfillable)
+  );
+}
+
+#[test]
+fn test_order_id_from_url() {
+  assert_eq!(
+    order_id_from_url("/orders/withoutShipmentDetail/2ab4c8b414124f0fa04072d615ec0610"),
+    Some("2ab4c8b414124f0fa04072d615ec0610")
+  );
+  assert_eq!(
+    order_id_from_url("/orders/withShipmentDetail/9fc4a1b2c3d4e5f60718293a4b5c6d7e/"),
+    Some("9fc4a1b2c3d4e5f60718293a4b5c6d7e")
+  );
+  assert_eq!(order_id_from_url("/orders/withoutShipmentDetail/"), None);
+  assert_eq!(order_id_from_url(""), None);
+}
+
+#[test]
+fn test_orders_for_status_yields_errors() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", "/orders/ready")
+    .with_body(r#"{"order_urls":["/orders/withoutShipmentDetail/aa01","/orders/withoutShipmentDetail/bb02"]}"#)
+    .create();
+  let detail_mock = server
+    .mock("GET", mockito::Matcher::Regex("^/orders/withoutShipmentDetail/".to_owned()))
+    .with_status(404)
+    .expect(2)
+    .create();
+
+  let client = get_mock_client(&server);
+  let orders = client.orders_for_status(OrderStatus::Ready).unwrap();
+  assert_eq!(orders.size_hint(), (2, Some(2)));
+  let results: Vec<_> = orders.collect();
+  assert_eq!(results.len(), 2);
+  assert!(results.iter().all(|res| matches!(res, Err(Error::NotFound { .. }))));
+  detail_mock.assert();
+}
+
+#[cfg(test)]
+fn mock_two_order_pages(server: &mut mockito::Server) -> mockito::Mock {
+  use crate::client::MOCK_TOKEN;
+
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", "/orders/ready")
+    .with_body(r#"{"order_urls":["/orders/withoutShipmentDetail/aa01"],"next_page_url":"/orders/ready?page=2"}"#)
+    .create();
+  server
+    .mock("GET", "/orders/ready?page=2")
+    .with_body(r#"{"order_urls":["/orders/withoutShipmentDetail/bb02"]}"#)
+    .create();
+  server
+    .mock("GET", mockito::Matcher::Regex("^/orders/withoutShipmentDetail/".to_owned()))
+    .with_body(include_str!("../test_data/order.json"))
+    .expect(2)
+    .create()
+}
+
+#[test]
+fn test_orders_for_status_follows_pages() {
+  use crate::client::get_mock_client;
+
+  let mut server = mockito::Server::new();
+  let detail_mock = mock_two_order_pages(&mut server);
+
+  let client = get_mock_client(&server);
+  let orders = client.orders_for_status(OrderStatus::Ready).unwrap();
+  assert_eq!(orders.size_hint(), (2, Some(2)));
+  assert_eq!(orders.map(Result::unwrap).count(), 2);
+  detail_mock.assert();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_async_orders_for_status_follows_pages() {
+  use crate::async_client::get_mock_async_client;
+
+  let (server, detail_mock) = tokio::task::spawn_blocking(|| {
+    let mut server = mockito::Server::new();
+    let detail_mock = mock_two_order_pages(&mut server);
+    (server, detail_mock)
+  })
+  .await
+  .unwrap();
+
+  let client = get_mock_async_client(&server);
+  let orders: Vec<_> = client.orders_for_status(OrderStatus::Ready).await.unwrap().collect().await;
+  assert_eq!(orders.len(), 2);
+  assert!(orders.iter().all(Result::is_ok));
+  detail_mock.assert();
+}
+
+#[test]
+fn test_exception_state_serde() {
+  assert_eq!(
+    serde_json::from_str::<ExceptionState>(r#""exception - too many units shipped""#).unwrap(),
+    ExceptionState::TooManyUnitsShipped
+  );
+  assert_eq!(
+    serde_json::from_str::<ExceptionState>(r#""exception - something new""#).unwrap(),
+    ExceptionState::Other("exception - something new".to_owned())
+  );
+  assert_eq!(
+    serde_json::to_string(&ExceptionState::Resolved).unwrap(),
+    r#""resolved""#
+  );
+  assert_eq!(
+    serde_json::to_string(&ExceptionState::Other("custom".to_owned())).unwrap(),
+    r#""custom""#
+  );
+}
+
+#[test]
+fn test_validate_ship_order() {
+  fn ship(carrier: &str, items: Vec<ShipOrderShipmentItem>) -> ShipOrder {
+    ShipOrder {
+      alt_order_id: None,
+      shipments: vec![ShipOrderShipment {
+        carrier: carrier.into(),
+        shipment_tracking_number: None,
+        shipment_items: items,
+        response_shipment_date: Utc::now(),
+      }],
+    }
+  }
+  fn item(quantity: i32) -> ShipOrderShipmentItem {
+    ShipOrderShipmentItem {
+      merchant_sku: "test_product".into(),
+      response_shipment_sku_quantity: quantity,
+      days_to_return: 30,
+      return_location: None,
+      rma_number: None,
+    }
+  }
+
+  assert_eq!(ship("UPS", vec![item(1)]).validate(), Ok(()));
+  assert_eq!(
+    ShipOrder { alt_order_id: None, shipments: vec![] }.validate(),
+    Err(ValidationError::NoShipments)
+  );
+  assert_eq!(
+    ship("UPS", vec![]).validate(),
+    Err(ValidationError::NoShipmentItems { shipment: 0 })
+  );
+  assert_eq!(
+    ship(" ", vec![item(1)]).validate(),
+    Err(ValidationError::EmptyCarrier { shipment: 0 })
+  );
+  assert_eq!(
+    ship("UPS", vec![item(1), item(-1)]).validate(),
+    Err(ValidationError::NegativeQuantity { shipment: 0, merchant_sku: "test_product".into() })
+  );
+
+  // rejected before any request is made
+  let client = crate::client::Client::new(crate::client::ClientOptions {
+    api_user: "mock-user".to_owned(),
+    secret: "mock-secret".to_owned(),
+    endpoint: Some("http://127.0.0.1:1".to_owned()),
+    ..Default::default()
+  }).unwrap();
+  match client.ship_order(&"2ab4c8b414124f0fa04072d615ec0610".into(), &ship("UPS", vec![])) {
+    Err(Error::Validation(ValidationError::NoShipmentItems { shipment: 0 })) => {}
+    other => panic!("unexpected result: {:?}", other),
+  }
+}
+
+#[cfg(test)]
+pub(crate) fn get_test_order() -> Order {
+  serde_json::from_str(include_str!("../test_data/order.json")).unwrap()
+}
+
+#[test]
+fn test_unknown_fields_are_kept() {
+  let order = get_test_order();
+  assert_eq!(
+    order.extra.get("order_transmission_date"),
+    Some(&serde_json::Value::from("2017-03-14T12:35:10Z"))
+  );
+  assert!(order.order_items[0].extra.is_empty());
+
+  let value = serde_json::to_value(&order).unwrap();
+  assert_eq!(value["order_transmission_date"], "2017-03-14T12:35:10Z");
+}
+
+#[test]
+fn test_get_sub_orders() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut parent = serde_json::to_value(get_test_order()).unwrap();
+  parent["sub_orders"] = serde_json::json!(["aa01"]);
+  let mut sub_order = serde_json::to_value(get_test_order()).unwrap();
+  sub_order["merchant_order_id"] = "aa01".into();
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", "/orders/withoutShipmentDetail/2ab4c8b414124f0fa04072d615ec0610")
+    .with_body(parent.to_string())
+    .create();
+  server
+    .mock("GET", "/orders/withoutShipmentDetail/aa01")
+    .with_body(sub_order.to_string())
+    .create();
+
+  let sub_orders = get_mock_client(&server)
+    .get_sub_orders(&"2ab4c8b414124f0fa04072d615ec0610".into())
+    .unwrap();
+  assert_eq!(sub_orders.len(), 1);
+  assert_eq!(sub_orders[0].merchant_order_id, "aa01");
+}
+
+#[test]
+fn test_get_orders_with() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+  use mockito::Matcher;
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let orders_mock = server
+    .mock("GET", "/orders/complete")
+    .match_query(Matcher::AllOf(vec![
+      Matcher::UrlEncoded("include_archived".to_owned(), "true".to_owned()),
+      Matcher::UrlEncoded("limit".to_owned(), "50".to_owned()),
+    ]))
+    .with_body(r#"{"order_urls":["/orders/withoutShipmentDetail/aa01"]}"#)
+    .create();
+
+  let res = get_mock_client(&server)
+    .get_orders_with(
+      OrderStatus::Complete,
+      GetOrdersOptions {
+        include_archived: Some(true),
+        limit: Some(50),
       },
     )
-    .unwrap()
+    .unwrap();
+  orders_mock.assert();
+  assert_eq!(res.order_urls.len(), 1);
+}
+
+#[test]
+fn test_remaining_quantities() {
+  let mut order = get_test_order();
+  let remaining = order.remaining_quantities();
+  assert_eq!(remaining["test_product"], 1);
+  assert_eq!(remaining["other_product"], 1);
+
+  order.shipments.as_mut().unwrap()[0].shipment_items[0].response_shipment_cancel_qty = Some(1);
+  let remaining = order.remaining_quantities();
+  assert_eq!(remaining["test_product"], 0);
+}
+
+#[test]
+fn test_unserialize_datetimes_without_offset() {
+  let mut order = serde_json::to_value(get_test_order()).unwrap();
+  order["order_placed_date"] = "2017-03-14T12:34:56.7654321".into();
+  order["order_detail"]["request_ship_by"] = "2017-03-15T12:34:56.1234567-04:00".into();
+  order["shipments"][0]["response_shipment_date"] = "2017-03-15T09:00:00.0000000".into();
+
+  let order: Order = serde_json::from_value(order).unwrap();
+  assert_eq!(order.order_placed_date.to_rfc3339(), "2017-03-14T12:34:56.765432100+00:00");
+  assert_eq!(order.order_detail.request_ship_by.to_rfc3339(), "2017-03-15T16:34:56.123456700+00:00");
+  assert_eq!(
+    order.shipments.unwrap()[0].response_shipment_date.unwrap().to_rfc3339(),
+    "2017-03-15T09:00:00+00:00"
+  );
+}
+
+#[test]
+fn test_order_status_from_str() {
+  for status in [
+    OrderStatus::Created,
+    OrderStatus::Ready,
+    OrderStatus::Acknowledged,
+    OrderStatus::Inprogress,
+    OrderStatus::Complete,
+  ] {
+    assert_eq!(status.to_string().parse::<OrderStatus>(), Ok(status.clone()));
+    assert_eq!(serde_json::to_value(&status).unwrap(), status.as_str());
+  }
+  assert_eq!(
+    "shipped".parse::<OrderStatus>(),
+    Err(ParseOrderStatusError("shipped".to_owned()))
+  );
+}
+
+#[test]
+fn test_unknown_order_status() {
+  use crate::client::get_mock_client;
+
+  let status: OrderStatus = serde_json::from_str(r#""on hold""#).unwrap();
+  assert_eq!(status, OrderStatus::Unknown("on hold".to_owned()));
+  assert_eq!(serde_json::to_string(&status).unwrap(), r#""on hold""#);
+
+  let mut order = serde_json::to_value(get_test_order()).unwrap();
+  order["status"] = "Ready".into();
+  let order: Order = serde_json::from_value(order).unwrap();
+  assert_eq!(order.status, OrderStatus::Unknown("Ready".to_owned()));
+
+  let server = mockito::Server::new();
+  match get_mock_client(&server).get_orders(order.status) {
+    Err(Error::Validation(ValidationError::UnknownOrderStatus { status })) => assert_eq!(status, "Ready"),
+    other => panic!("unexpected result: {:?}", other),
+  }
+}
+
+#[test]
+fn test_reconcile_totals() {
+  let mut order = get_test_order();
+  let totals = order.computed_totals();
+  assert_eq!(totals.item_price.as_ref().unwrap().base_price.to_string(), "54.97");
+  assert_eq!(totals.item_fees.unwrap().to_string(), "8.25");
+  assert!(order.reconcile().is_balanced());
+
+  order.order_items[0].item_fees = order.order_items[1].item_fees;
+  let discrepancy = order.reconcile();
+  assert!(!discrepancy.is_balanced());
+  assert_eq!(discrepancy.item_fees.to_string(), "3.75");
+  assert!(is_zero_amount(discrepancy.base_price));
+}
+
+#[test]
+fn test_unserialize_missing_and_null_amounts() {
+  let omitted: OrderTotals = serde_json::from_str(r#"{}"#).unwrap();
+  let nulls: OrderTotals = serde_json::from_str(r#"{
+    "item_price": null,
+    "item_fees": null,
+    "fee_adjustments": null,
+    "regulatory_fees": null
+  }"#).unwrap();
+  for totals in [omitted, nulls] {
+    assert!(totals.item_price.is_none());
+    assert!(totals.item_fees.is_none());
+    assert!(totals.fee_adjustments.is_none());
+    assert!(totals.regulatory_fees.is_none());
+  }
+
+  let mut item = serde_json::to_value(&get_test_order().order_items[0]).unwrap();
+  let fields = ["price_adjustment", "item_fees", "fee_adjustments", "tax_info", "regulatory_fees"];
+  for field in fields {
+    item.as_object_mut().unwrap().remove(field);
+  }
+  item["item_price"].as_object_mut().unwrap().remove("item_tax");
+  item["item_price"]["item_shipping_tax"] = serde_json::Value::Null;
+  let item: OrderItem = serde_json::from_value(item).unwrap();
+  assert!(item.item_fees.is_none() && item.regulatory_fees.is_none() && item.tax_info.is_none());
+  assert!(item.item_price.item_tax.is_none() && item.item_price.item_shipping_tax.is_none());
+}
+
+#[test]
+fn test_tax_info_jurisdictions() {
+  let amount = |value: &str| value.parse::<Money>().unwrap();
+  let info: TaxInfo = serde_json::from_str(r#"{
+    "tax_code": "4000000",
+    "state_tax": null,
+    "county_tax": null,
+    "city_tax": null,
+    "special_tax": null,
+    "tax_exempt": false,
+    "jurisdictions": [
+      { "jurisdiction_type": "State", "jurisdiction_name": "NY", "tax_rate": 0.04, "tax_amount": 1.6 },
+      { "jurisdiction_type": "city", "jurisdiction_name": "New York", "tax_rate": 0.045, "tax_amount": 1.8 },
+      { "jurisdiction_type": "district", "jurisdiction_name": "MCTD", "tax_amount": 0.15 }
+    ]
+  }"#).unwrap();
+  assert_eq!(info.jurisdictions[0].jurisdiction_type, JurisdictionType::State);
+  assert_eq!(info.jurisdictions[2].jurisdiction_type, JurisdictionType::Other("district".to_owned()));
+  assert!(is_zero_amount(info.total_tax() - amount("3.55")));
+
+  let flat: TaxInfo = serde_json::from_str(r#"{"tax_code":"4000000","state_tax":1.0,"county_tax":0.5,"city_tax":null,"special_tax":null}"#).unwrap();
+  assert!(flat.jurisdictions.is_empty());
+  assert!(is_zero_amount(flat.total_tax() - amount("1.5")));
+
+  let mut order = get_test_order();
+  order.order_items[0].tax_info = Some(info.clone());
+  order.order_items[1].tax_info = Some(info);
+  let total = order.computed_totals().tax_info.unwrap();
+  assert_eq!(total.tax_code.as_deref(), Some("4000000"));
+  assert_eq!(total.tax_exempt, Some(false));
+  assert_eq!(total.jurisdictions.len(), 3);
+  assert!(is_zero_amount(total.jurisdictions[0].tax_amount - amount("3.2")));
+  assert!(is_zero_amount(total.total_tax() - amount("7.1")));
+}
+
+#[test]
+fn test_tax_info_aggregate_mixed() {
+  let amount = |value: &str| value.parse::<Money>().unwrap();
+  let itemized: TaxInfo = serde_json::from_str(r#"{
+    "tax_code": "4000000",
+    "state_tax": null,
+    "county_tax": null,
+    "city_tax": null,
+    "special_tax": null,
+    "jurisdictions": [{ "jurisdiction_type": "state", "jurisdiction_name": "NY", "tax_amount": 1.6 }]
+  }"#).unwrap();
+  let flat: TaxInfo = serde_json::from_str(r#"{"tax_code":"4000000","state_tax":1.0,"county_tax":0.25,"city_tax":null,"special_tax":null}"#).unwrap();
+
+  let total = TaxInfo::aggregate([&itemized, &flat]);
+  assert!(is_zero_amount(total.total_tax() - amount("2.85")));
+  assert!(is_zero_amount(total.total_tax() - (itemized.total_tax() + flat.total_tax())));
+  assert_eq!(total.jurisdictions.len(), 3);
+  assert!(is_zero_amount(total.state_tax.unwrap() - amount("1.0")));
+
+  let total = TaxInfo::aggregate([&flat, &flat]);
+  assert!(total.jurisdictions.is_empty());
+  assert!(is_zero_amount(total.total_tax() - amount("2.5")));
+}
+
+#[test]
+fn test_order_diff() {
+  let current = get_test_order();
+  assert!(current.diff(&get_test_order()).is_empty());
+
+  let mut previous = get_test_order();
+  previous.status = OrderStatus::Acknowledged;
+  previous.shipments = None;
+  let changes = current.diff(&previous);
+
+  assert_eq!(changes.status, Some((OrderStatus::Acknowledged, OrderStatus::Inprogress)));
+  assert!(changes.acknowledgement_status.is_none());
+  assert_eq!(changes.new_shipments.len(), 1);
+  assert_eq!(changes.remaining_quantities.len(), 1);
+  assert_eq!(changes.remaining_quantities["test_product"], (2, 1));
+}
+
+#[test]
+fn test_item_lookup() {
+  let mut order = get_test_order();
+  assert_eq!(order.item_by_sku("other_product").unwrap().request_order_quantity, 1);
+  assert!(order.item_by_sku("missing").is_none());
+
+  let id = order.order_items[1].order_item_id.clone();
+  assert_eq!(order.item_by_order_item_id(id.as_str()).unwrap().merchant_sku, "other_product");
+
+  order.order_items[1].merchant_sku = "test_product".into();
+  assert_eq!(order.items_by_sku("test_product").count(), 2);
+  assert_eq!(order.item_by_sku("test_product").unwrap().request_order_quantity, 2);
+}
+
+#[test]
+fn test_adjustment_serde() {
+  let fee: FeeAdjustment = serde_json::from_str(r#"{
+    "adjustment_name": "Jet Commission",
+    "adjustment_type": "Commission",
+    "commission_id": "c1",
+    "value": 1.5
+  }"#).unwrap();
+  assert_eq!(fee.adjustment_type, AdjustmentType::Commission);
+  assert_eq!(
+    serde_json::from_str::<AdjustmentType>(r#""Storage""#).unwrap(),
+    AdjustmentType::Other("Storage".to_owned())
+  );
+  assert_eq!(
+    serde_json::from_str::<AdjustmentReason>(r#""price match""#).unwrap(),
+    AdjustmentReason::Other("price match".to_owned())
+  );
+
+  let reason: AdjustmentReason = serde_json::from_str(r#""item damaged""#).unwrap();
+  assert_eq!(reason, AdjustmentReason::ItemDamaged);
+  assert_eq!(serde_json::to_string(&AdjustmentReason::PriceOverride).unwrap(), r#""price override""#);
+  assert_eq!(
+    serde_json::to_string(&AdjustmentReason::Other("recall".to_owned())).unwrap(),
+    r#""recall""#
+  );
+}
+
+#[test]
+fn test_customer_order_comments() {
+  assert_eq!(get_test_order().customer_order_comments, None);
+
+  let mut order = serde_json::to_value(get_test_order()).unwrap();
+  order["customer_order_comments"] = "Leave at the side door".into();
+  let order: Order = serde_json::from_value(order).unwrap();
+  assert_eq!(order.customer_order_comments.as_deref(), Some("Leave at the side door"));
+  assert!(!order.extra.contains_key("customer_order_comments"));
+}
+
+#[test]
+fn test_ship_by_priority() {
+  let mut order = get_test_order();
+  order.order_detail.request_ship_by = Utc::now() - Duration::minutes(5);
+  assert!(order.order_detail.is_past_due());
+  assert_eq!(order.priority(), ShipByPriority::PastDue);
+
+  order.order_detail.request_ship_by = Utc::now() + Duration::hours(30);
+  assert!(!order.order_detail.is_past_due());
+  assert!(order.order_detail.time_until_ship_by() > Duration::hours(29));
+  assert_eq!(order.priority(), ShipByPriority::DueSoon);
+
+  assert_eq!(ShipByPriority::from_time_left(Duration::hours(2)), ShipByPriority::DueToday);
+  assert_eq!(ShipByPriority::from_time_left(Duration::days(5)), ShipByPriority::Later);
+  assert!(ShipByPriority::PastDue < ShipByPriority::Later);
+}
+
+#[test]
+fn test_serialized_field_names() {
+  use chrono::TimeZone;
+
+  fn keys(value: &serde_json::Value) -> Vec<&str> {
+    let mut keys: Vec<&str> = value.as_object().unwrap().keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    keys
+  }
+
+  let mut ship = ShipOrder::builder()
+    .shipment(|s| s.carrier("UPS").tracking("1Z12342452342").item("test_product", 1, 30))
+    .build();
+  ship.shipments[0].response_shipment_date = Utc.with_ymd_and_hms(2017, 3, 15, 9, 0, 0).unwrap();
+  ship.shipments[0].shipment_items[0].rma_number = Some("RMA-1".to_owned());
+  let value = serde_json::to_value(&ship).unwrap();
+  assert_eq!(keys(&value), ["alt_order_id", "shipments"]);
+  let shipment = &value["shipments"][0];
+  assert_eq!(
+    keys(shipment),
+    ["carrier", "response_shipment_date", "shipment_items", "shipment_tracking_number"]
+  );
+  assert_eq!(shipment["response_shipment_date"], "2017-03-15T09:00:00.0000000-00:00");
+  assert_eq!(
+    keys(&shipment["shipment_items"][0]),
+    ["RMA_number", "days_to_return", "merchant_sku", "response_shipment_sku_quantity"]
+  );
+
+  let ack = AcknowledgeOrder {
+    acknowledgement_status: AcknowledgementStatus::Accepted,
+    alt_order_id: None,
+    order_items: vec![AcknowledgeOrderItem {
+      order_item_acknowledgement_status: ItemAcknowledgementStatus::Fulfillable,
+      order_item_id: "2906d22b212d4745ab9986b80b1ad2af".into(),
+      alt_order_item_id: None,
+    }],
+  };
+  let value = serde_json::to_value(&ack).unwrap();
+  assert_eq!(keys(&value), ["acknowledgement_status", "alt_order_id", "order_items"]);
+  assert_eq!(
+    keys(&value["order_items"][0]),
+    ["alt_order_item_id", "order_item_acknowledgement_status", "order_item_id"]
+  );
+}
+
+#[test]
+fn test_order_roundtrip() {
+  let value = serde_json::to_value(get_test_order()).unwrap();
+  let order: Order = serde_json::from_value(value.clone()).unwrap();
+  assert_eq!(serde_json::to_value(order).unwrap(), value);
+}
+
+#[test]
+fn test_partial_acknowledgement() {
+  let order = get_test_order();
+  let ack = AcknowledgeOrder::partial(
+    &order,
+    &[("other_product", ItemAcknowledgementStatus::NonfulfillableNoInventory)],
+  )
+  .unwrap();
+  assert_eq!(ack.acknowledgement_status, AcknowledgementStatus::Accepted);
+  assert_eq!(ack.order_items.len(), order.order_items.len());
+  assert_eq!(ack.order_items[0].order_item_id, order.order_items[0].order_item_id);
+  assert_eq!(ack.order_items[0].order_item_acknowledgement_status, ItemAcknowledgementStatus::Fulfillable);
+  assert_eq!(
+    ack.order_items[1].order_item_acknowledgement_status,
+    ItemAcknowledgementStatus::NonfulfillableNoInventory
+  );
+
+  let ack = AcknowledgeOrder::partial(
+    &order,
+    &[
+      ("test_product", ItemAcknowledgementStatus::NonfulfillableNoInventory),
+      ("other_product", ItemAcknowledgementStatus::NonfulfillableInvalidMerchantSku),
+    ],
+  )
+  .unwrap();
+  assert_eq!(ack.acknowledgement_status, AcknowledgementStatus::RejectedItemLevelError);
+
+  assert_eq!(
+    AcknowledgeOrder::partial(&order, &[("missing", ItemAcknowledgementStatus::NonfulfillableNoInventory)])
+      .unwrap_err(),
+    ValidationError::UnknownSku { merchant_sku: "missing".into() }
+  );
+}
+
+#[test]
+fn test_reject_order() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+  use mockito::Matcher;
+  use serde_json::json;
+
+  let order = get_test_order();
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let ack_mock = server
+    .mock("PUT", "/orders/2ab4c8b414124f0fa04072d615ec0610/acknowledge")
+    .match_body(Matcher::PartialJson(json!({ "acknowledgement_status": "rejected - fraud" })))
+    .create();
+
+  get_mock_client(&server).reject_order(&order, RejectReason::Fraud).unwrap();
+  ack_mock.assert();
+
+  let ack = AcknowledgeOrder::reject(&order, RejectReason::UnfulfillableAddress);
+  assert_eq!(ack.acknowledgement_status.as_str(), "rejected - unfulfillable address");
+  assert_eq!(ack.order_items.len(), order.order_items.len());
+  assert!(ack
+    .order_items
+    .iter()
+    .all(|item| item.order_item_acknowledgement_status == ItemAcknowledgementStatus::Fulfillable));
+
+  let ack = AcknowledgeOrder::reject(&order, RejectReason::ItemLevelError);
+  assert_eq!(ack.acknowledgement_status, AcknowledgementStatus::RejectedItemLevelError);
+  assert!(ack
+    .order_items
+    .iter()
+    .all(|item| item.order_item_acknowledgement_status == ItemAcknowledgementStatus::NonfulfillableNoInventory));
+}
+
+#[test]
+fn test_get_orders_response_order_ids() {
+  let res: GetOrdersResponse = serde_json::from_str(r#"{"order_urls":[
+    "/orders/withoutShipmentDetail/2ab4c8b414124f0fa04072d615ec0610",
+    "/orders/withoutShipmentDetail/",
+    "/orders/withoutShipmentDetail/d2a8d7e0b1f94c2a9b3e6f5c4d3b2a19/"
+  ]}"#).unwrap();
+  assert_eq!(res.order_ids(), ["2ab4c8b414124f0fa04072d615ec0610", "d2a8d7e0b1f94c2a9b3e6f5c4d3b2a19"]);
 }
 
 #[test]
@@ -385,7 +3036,7 @@ fn test_download_all_orders() {
 
   let mut orders = vec![];
 
-  for status in vec![
+  for status in [
     OrderStatus::Created,
     OrderStatus::Ready,
     OrderStatus::Acknowledged,
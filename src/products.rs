@@ -2,12 +2,25 @@
 //! [Jet Documentation](https://developer.jet.com/docs/overview)
 //!
 
-use super::client::{Client, Method};
+#[cfg(feature = "async")]
+use super::async_client::AsyncClient;
+#[cfg(feature = "async")]
+use futures_util::stream::{self, StreamExt};
+use super::bulk::{gzip, BulkUploadHandle, FileType};
+use super::client::{Client, Method, PreparedRequest};
+use chrono::{DateTime, Utc};
 use crate::error::*;
+use crate::money::{deserialize_price, deserialize_price_opt, serialize_price, Money};
+use crate::utils::parallel_map;
+use reqwest::header::{HeaderMap, DATE};
+pub use crate::nodes::FulfillmentNodeId;
+
+/// Number of inventory requests `get_inventory_bulk` keeps in flight
+pub const INVENTORY_BULK_CONCURRENCY: usize = 4;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InventoryFulfillmentNode {
-  pub fulfillment_node_id: String,
+  pub fulfillment_node_id: FulfillmentNodeId,
   pub quantity: i32,
 }
 
@@ -16,20 +29,303 @@ pub struct Inventory {
   pub fulfillment_nodes: Vec<InventoryFulfillmentNode>,
 }
 
+/// Confirmation of an inventory update. Jet doesn't echo the inventory
+/// back, so `inventory` is what was sent and `updated_at` is the response's
+/// `Date` header, or the local time if Jet didn't send one.
+#[derive(Debug)]
+pub struct InventoryUpdate {
+  pub merchant_sku: String,
+  pub inventory: Inventory,
+  pub updated_at: DateTime<Utc>,
+}
+
+impl InventoryUpdate {
+  fn new(merchant_sku: &str, inventory: Inventory, headers: &HeaderMap) -> Self {
+    let updated_at = headers
+      .get(DATE)
+      .and_then(|value| value.to_str().ok())
+      .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+      .map_or_else(Utc::now, |date| date.with_timezone(&Utc));
+    InventoryUpdate {
+      merchant_sku: merchant_sku.to_owned(),
+      inventory,
+      updated_at,
+    }
+  }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Price {
-  pub price: f32,
+  #[serde(serialize_with = "serialize_price", deserialize_with = "deserialize_price")]
+  pub price: Money,
+}
+
+/// Price override for a single fulfillment node
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NodePrice {
+  pub fulfillment_node_id: FulfillmentNodeId,
+  #[serde(serialize_with = "serialize_price", deserialize_with = "deserialize_price")]
+  pub fulfillment_node_price: Money,
+}
+
+/// New price of a SKU for `update_price`, optionally with per node prices
+#[derive(Debug, Serialize)]
+pub struct PriceUpdate {
+  /// Base price, used by nodes without their own price
+  #[serde(serialize_with = "serialize_price")]
+  pub price: Money,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub fulfillment_nodes: Option<Vec<NodePrice>>,
+}
+
+impl From<Price> for PriceUpdate {
+  fn from(value: Price) -> Self {
+    PriceUpdate {
+      price: value.price,
+      fulfillment_nodes: None,
+    }
+  }
+}
+
+/// Price currently set for a SKU, as returned by Jet
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PriceResponse {
+  /// Base price of the SKU
+  #[serde(deserialize_with = "deserialize_price")]
+  pub price: Money,
+  /// Node-level prices, for merchants pricing differently per fulfillment node
+  pub fulfillment_nodes: Option<Vec<NodePrice>>,
+  /// When the price was last updated
+  pub price_last_update: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StandardProductCode {
+  pub standard_product_code: String,
+  /// One of 'GTIN-14', 'EAN', 'ISBN-10', 'ISBN-13', 'UPC'
+  pub standard_product_code_type: String,
+}
+
+/// A merchant SKU record in Jet's catalog
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MerchantSku {
+  pub product_title: String,
+  pub product_description: Option<String>,
+  pub brand: Option<String>,
+  pub manufacturer: Option<String>,
+  pub mfr_part_number: Option<String>,
+  /// Number of items with the given product code in the SKU
+  pub multipack_quantity: Option<i32>,
+  pub standard_product_codes: Option<Vec<StandardProductCode>>,
+  #[serde(rename = "ASIN")]
+  pub asin: Option<String>,
+  pub jet_browse_node_id: Option<i64>,
+  /// Up to 5 short marketing bullet points
+  pub bullets: Option<Vec<String>>,
+  pub main_image_url: Option<String>,
+  /// Image of the color or pattern, shown when choosing between variations
+  pub swatch_image_url: Option<String>,
+  /// Further product images, in display order
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub additional_images: Vec<String>,
+}
+
+/// Listing status of a SKU. Values Jet adds in the future are kept in
+/// `Other`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum ListingStatus {
+  /// 'Unsubmitted' - The SKU has not been submitted for review
+  Unsubmitted,
+  /// 'Under Jet Review' - Jet is reviewing the SKU
+  UnderReview,
+  /// 'Missing Listing Data' - Required fields such as price or inventory are missing
+  MissingListingData,
+  /// 'Available for Purchase' - The SKU is listed
+  Listed,
+  /// 'Excluded' - Jet rejected the SKU
+  Rejected,
+  /// 'Archived' - The merchant archived the SKU
+  Archived,
+  Other(String),
+}
+
+impl ListingStatus {
+  pub fn as_str(&self) -> &str {
+    match *self {
+      ListingStatus::Unsubmitted => "Unsubmitted",
+      ListingStatus::UnderReview => "Under Jet Review",
+      ListingStatus::MissingListingData => "Missing Listing Data",
+      ListingStatus::Listed => "Available for Purchase",
+      ListingStatus::Rejected => "Excluded",
+      ListingStatus::Archived => "Archived",
+      ListingStatus::Other(ref value) => value,
+    }
+  }
+}
+
+impl From<String> for ListingStatus {
+  fn from(value: String) -> Self {
+    match value.as_str() {
+      "Unsubmitted" => ListingStatus::Unsubmitted,
+      "Under Jet Review" => ListingStatus::UnderReview,
+      "Missing Listing Data" => ListingStatus::MissingListingData,
+      "Available for Purchase" => ListingStatus::Listed,
+      "Excluded" => ListingStatus::Rejected,
+      "Archived" => ListingStatus::Archived,
+      _ => ListingStatus::Other(value),
+    }
+  }
+}
+
+impl From<ListingStatus> for String {
+  fn from(value: ListingStatus) -> Self {
+    match value {
+      ListingStatus::Other(value) => value,
+      known => known.as_str().to_owned(),
+    }
+  }
+}
+
+/// Whether Jet has accepted and listed a SKU
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SkuStatus {
+  pub sku: String,
+  pub status: ListingStatus,
+  /// Details on why the SKU is not listed, e.g. missing fields
+  pub sub_status: Option<Vec<String>>,
+  pub sku_last_update: Option<DateTime<Utc>>,
+}
+
+/// The images set on a SKU and the image problems Jet reported, see
+/// `Client::get_image_status`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageStatus {
+  pub main_image_url: Option<String>,
+  pub swatch_image_url: Option<String>,
+  pub additional_images: Vec<String>,
+  /// Entries of the SKU's `sub_status` about images, e.g. 'Missing Main Image'
+  pub issues: Vec<String>,
+}
+
+impl ImageStatus {
+  fn new(sku: MerchantSku, status: &SkuStatus) -> Self {
+    ImageStatus {
+      main_image_url: sku.main_image_url,
+      swatch_image_url: sku.swatch_image_url,
+      additional_images: sku.additional_images,
+      issues: status
+        .sub_status
+        .iter()
+        .flatten()
+        .filter(|sub_status| sub_status.to_ascii_lowercase().contains("image"))
+        .cloned()
+        .collect(),
+    }
+  }
+
+  /// Whether the SKU has a main image and Jet reported no image problems
+  pub fn is_complete(&self) -> bool {
+    self.main_image_url.is_some() && self.issues.is_empty()
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShippingException {
+  /// One of 'SecondDay', 'NextDay', 'Scheduled', 'Expedited', 'Standard'
+  pub service_level: Option<String>,
+  pub shipping_method: Option<String>,
+  /// 'Override charge' or 'Additional charge'
+  pub override_type: Option<String>,
+  #[serde(rename = "shipping_charge_amount", default, deserialize_with = "deserialize_price_opt")]
+  pub shipping_charge: Option<Money>,
+  /// 'exclusive' to only offer this method, 'restricted' to block it
+  pub shipping_exception_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShippingExceptionsFulfillmentNode {
+  pub fulfillment_node_id: FulfillmentNodeId,
+  pub shipping_exceptions: Vec<ShippingException>,
+}
+
+/// Per-SKU overrides of the shipping options offered from each fulfillment node
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShippingExceptions {
+  pub fulfillment_nodes: Vec<ShippingExceptionsFulfillmentNode>,
+}
+
+/// How the child SKUs of a `Variation` relate to the parent SKU
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VariationRelationship {
+  /// The children are variants of the parent, e.g. sizes or colors
+  Variation,
+  /// The children are accessories sold alongside the parent
+  Accessory,
+}
+
+/// Groups SKUs under a parent SKU, e.g. the sizes and colors of a shirt
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Variation {
+  pub relationship: VariationRelationship,
+  /// Jet attribute IDs the children differ by, e.g. the size attribute
+  pub variation_refinements: Vec<i64>,
+  /// merchant_skus of the related SKUs
+  pub children_skus: Vec<String>,
+  /// Title shown for the whole group
+  pub group_title: Option<String>,
+}
+
+/// Per-SKU overrides of where and how the SKU can be returned
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReturnsExceptions {
+  /// Number of days the customer has to return the SKU
+  pub time_to_return: Option<i32>,
+  /// Return locations the SKU can be sent back to
+  pub return_location_ids: Vec<String>,
+  /// Shipping methods allowed for the return, e.g. 'UPS Ground'
+  pub return_shipping_methods: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ArchiveSku {
+  is_archived: bool,
 }
 
 impl Client {
-  pub fn update_inventory(&self, sku_id: &str, data: Inventory) -> Result<()> {
-    self.request(
+  /// Sets the inventory of a SKU and returns what Jet accepted
+  pub fn update_inventory(&self, sku_id: &str, data: Inventory) -> Result<InventoryUpdate> {
+    let headers = self.request_headers(
       Method::PUT,
       &format!("/merchant-skus/{}/inventory", sku_id),
       |req| {
         req.json(&data)
       },
-    )
+    )?;
+    Ok(InventoryUpdate::new(sku_id, data, &headers))
+  }
+
+  /// Sets the inventory of a SKU to 0 at each of `node_ids`, or at every
+  /// node it currently has inventory at if `node_ids` is empty
+  pub fn zero_inventory(&self, sku_id: &str, node_ids: &[String]) -> Result<()> {
+    let node_ids: Vec<FulfillmentNodeId> = if node_ids.is_empty() {
+      self
+        .get_inventory(sku_id)?
+        .fulfillment_nodes
+        .into_iter()
+        .map(|node| node.fulfillment_node_id)
+        .collect()
+    } else {
+      node_ids.iter().cloned().map(FulfillmentNodeId::from).collect()
+    };
+    let inventory = Inventory {
+      fulfillment_nodes: node_ids
+        .into_iter()
+        .map(|fulfillment_node_id| InventoryFulfillmentNode { fulfillment_node_id, quantity: 0 })
+        .collect(),
+    };
+    self.update_inventory(sku_id, inventory)?;
+    Ok(())
   }
 
   pub fn get_inventory(&self, sku_id: &str) -> Result<Inventory> {
@@ -40,8 +336,17 @@ impl Client {
     )
   }
 
-  pub fn update_price(&self, sku_id: &str, data: Price) -> Result<()> {
-    self.request(
+  /// Fetches the inventory of each of `skus`, up to
+  /// `INVENTORY_BULK_CONCURRENCY` at a time. Results are in the same order as
+  /// `skus`, a failed SKU doesn't stop the others.
+  pub fn get_inventory_bulk(&self, skus: &[String]) -> Vec<(String, Result<Inventory>)> {
+    parallel_map(skus, INVENTORY_BULK_CONCURRENCY, |sku| (sku.clone(), self.get_inventory(sku)))
+  }
+
+  /// Sets the base price of a SKU and, if given, per fulfillment node prices
+  pub fn update_price<T: Into<PriceUpdate>>(&self, sku_id: &str, data: T) -> Result<()> {
+    let data = data.into();
+    self.request_no_content(
       Method::PUT,
       &format!("/merchant-skus/{}/price", sku_id),
       |req| {
@@ -50,11 +355,722 @@ impl Client {
     )
   }
 
-  pub fn get_price(&self, sku_id: &str) -> Result<Price> {
+  pub fn get_price(&self, sku_id: &str) -> Result<PriceResponse> {
+    self.request(
+      Method::GET,
+      &format!("/merchant-skus/{}/price", sku_id),
+      std::convert::identity,
+    )
+  }
+
+  /// Archives (`true`) or unarchives (`false`) a SKU, removing it from or
+  /// restoring it to the catalog
+  pub fn archive_sku(&self, sku_id: &str, archive: bool) -> Result<()> {
+    self.request_no_content(
+      Method::PUT,
+      &format!("/merchant-skus/{}/status/archive", sku_id),
+      |req| {
+        req.json(&ArchiveSku { is_archived: archive })
+      },
+    )
+  }
+
+  /// Creates or updates the SKU record
+  pub fn upsert_sku(&self, sku_id: &str, sku: &MerchantSku) -> Result<()> {
+    self.request_no_content(
+      Method::PUT,
+      &format!("/merchant-skus/{}", sku_id),
+      |req| {
+        req.json(sku)
+      },
+    )
+  }
+
+  pub fn get_sku(&self, sku_id: &str) -> Result<MerchantSku> {
+    self.request(
+      Method::GET,
+      &format!("/merchant-skus/{}", sku_id),
+      std::convert::identity,
+    )
+  }
+
+  pub fn get_sku_status(&self, sku_id: &str) -> Result<SkuStatus> {
+    self.request(
+      Method::GET,
+      &format!("/merchant-skus/{}/status", sku_id),
+      std::convert::identity,
+    )
+  }
+
+  /// The images of a SKU and whether Jet ingested them. Jet has no
+  /// separate image endpoint, this combines `get_sku` and `get_sku_status`.
+  pub fn get_image_status(&self, sku_id: &str) -> Result<ImageStatus> {
+    let sku = self.get_sku(sku_id)?;
+    let status = self.get_sku_status(sku_id)?;
+    Ok(ImageStatus::new(sku, &status))
+  }
+
+  pub fn update_shipping_exceptions(&self, sku_id: &str, data: &ShippingExceptions) -> Result<()> {
+    self.request_no_content(
+      Method::PUT,
+      &format!("/merchant-skus/{}/shippingexception", sku_id),
+      |req| {
+        req.json(data)
+      },
+    )
+  }
+
+  pub fn get_shipping_exceptions(&self, sku_id: &str) -> Result<ShippingExceptions> {
+    self.request(
+      Method::GET,
+      &format!("/merchant-skus/{}/shippingexception", sku_id),
+      std::convert::identity,
+    )
+  }
+
+  /// Sets the child SKUs of the parent SKU `sku_id`
+  pub fn update_variation(&self, sku_id: &str, relation: &Variation) -> Result<()> {
+    self.request_no_content(
+      Method::PUT,
+      &format!("/merchant-skus/{}/variation", sku_id),
+      |req| {
+        req.json(relation)
+      },
+    )
+  }
+
+  pub fn get_variation(&self, sku_id: &str) -> Result<Variation> {
+    self.request(
+      Method::GET,
+      &format!("/merchant-skus/{}/variation", sku_id),
+      std::convert::identity,
+    )
+  }
+
+  pub fn update_returns_exceptions(&self, sku_id: &str, data: &ReturnsExceptions) -> Result<()> {
+    self.request_no_content(
+      Method::PUT,
+      &format!("/merchant-skus/{}/returnsexception", sku_id),
+      |req| {
+        req.json(data)
+      },
+    )
+  }
+
+  pub fn get_returns_exceptions(&self, sku_id: &str) -> Result<ReturnsExceptions> {
+    self.request(
+      Method::GET,
+      &format!("/merchant-skus/{}/returnsexception", sku_id),
+      std::convert::identity,
+    )
+  }
+
+  /// Updates the inventory of many SKUs at once through Jet's bulk file
+  /// upload. Returns a handle to poll the file's processing status.
+  pub fn update_inventory_bulk(&self, updates: &[(String, Inventory)]) -> Result<BulkUploadHandle> {
+    let data: serde_json::Map<String, serde_json::Value> = updates
+      .iter()
+      .map(|(sku_id, inventory)| Ok((sku_id.clone(), serde_json::to_value(inventory)?)))
+      .collect::<Result<_>>()?;
+    let token = self.get_upload_url()?;
+    self.upload_bulk_data(&token, gzip(&serde_json::to_vec(&data)?)?, FileType::Inventory, "inventory.json.gz")
+  }
+
+  /// Same as `update_inventory` but only returns the request it would send
+  pub fn preview_update_inventory(&self, sku_id: &str, data: &Inventory) -> Result<PreparedRequest> {
+    self.prepare(
+      Method::PUT,
+      &format!("/merchant-skus/{}/inventory", sku_id),
+      |req| {
+        req.json(data)
+      },
+    )
+  }
+
+  /// Same as `update_price` but only returns the request it would send
+  pub fn preview_update_price(&self, sku_id: &str, data: &PriceUpdate) -> Result<PreparedRequest> {
+    self.prepare(
+      Method::PUT,
+      &format!("/merchant-skus/{}/price", sku_id),
+      |req| {
+        req.json(data)
+      },
+    )
+  }
+
+  /// Same as `archive_sku` but only returns the request it would send
+  pub fn preview_archive_sku(&self, sku_id: &str, archive: bool) -> Result<PreparedRequest> {
+    self.prepare(
+      Method::PUT,
+      &format!("/merchant-skus/{}/status/archive", sku_id),
+      |req| {
+        req.json(&ArchiveSku { is_archived: archive })
+      },
+    )
+  }
+
+  /// Same as `upsert_sku` but only returns the request it would send
+  pub fn preview_upsert_sku(&self, sku_id: &str, sku: &MerchantSku) -> Result<PreparedRequest> {
+    self.prepare(
+      Method::PUT,
+      &format!("/merchant-skus/{}", sku_id),
+      |req| {
+        req.json(sku)
+      },
+    )
+  }
+}
+
+#[cfg(feature = "async")]
+impl AsyncClient {
+  /// Sets the inventory of a SKU and returns what Jet accepted
+  pub async fn update_inventory(&self, sku_id: &str, data: Inventory) -> Result<InventoryUpdate> {
+    let headers = self.request_headers(
+      Method::PUT,
+      &format!("/merchant-skus/{}/inventory", sku_id),
+      |req| {
+        req.json(&data)
+      },
+    ).await?;
+    Ok(InventoryUpdate::new(sku_id, data, &headers))
+  }
+
+  /// Sets the inventory of a SKU to 0 at each of `node_ids`, or at every
+  /// node it currently has inventory at if `node_ids` is empty
+  pub async fn zero_inventory(&self, sku_id: &str, node_ids: &[String]) -> Result<()> {
+    let node_ids: Vec<FulfillmentNodeId> = if node_ids.is_empty() {
+      self
+        .get_inventory(sku_id).await?
+        .fulfillment_nodes
+        .into_iter()
+        .map(|node| node.fulfillment_node_id)
+        .collect()
+    } else {
+      node_ids.iter().cloned().map(FulfillmentNodeId::from).collect()
+    };
+    let inventory = Inventory {
+      fulfillment_nodes: node_ids
+        .into_iter()
+        .map(|fulfillment_node_id| InventoryFulfillmentNode { fulfillment_node_id, quantity: 0 })
+        .collect(),
+    };
+    self.update_inventory(sku_id, inventory).await?;
+    Ok(())
+  }
+
+  pub async fn get_inventory(&self, sku_id: &str) -> Result<Inventory> {
     self.request(
       Method::GET,
       &format!("/merchant-skus/{}/inventory", sku_id),
       std::convert::identity,
+    ).await
+  }
+
+  /// Fetches the inventory of each of `skus`, up to
+  /// `INVENTORY_BULK_CONCURRENCY` at a time. Results are in the same order as
+  /// `skus`, a failed SKU doesn't stop the others.
+  pub async fn get_inventory_bulk(&self, skus: &[String]) -> Vec<(String, Result<Inventory>)> {
+    stream::iter(skus)
+      .map(|sku| async move { (sku.clone(), self.get_inventory(sku).await) })
+      .buffered(INVENTORY_BULK_CONCURRENCY)
+      .collect()
+      .await
+  }
+
+  /// Sets the base price of a SKU and, if given, per fulfillment node prices
+  pub async fn update_price<T: Into<PriceUpdate>>(&self, sku_id: &str, data: T) -> Result<()> {
+    let data = data.into();
+    self.request_no_content(
+      Method::PUT,
+      &format!("/merchant-skus/{}/price", sku_id),
+      |req| {
+        req.json(&data)
+      },
+    ).await
+  }
+
+  pub async fn get_price(&self, sku_id: &str) -> Result<PriceResponse> {
+    self.request(
+      Method::GET,
+      &format!("/merchant-skus/{}/price", sku_id),
+      std::convert::identity,
+    ).await
+  }
+
+  /// Archives (`true`) or unarchives (`false`) a SKU, removing it from or
+  /// restoring it to the catalog
+  pub async fn archive_sku(&self, sku_id: &str, archive: bool) -> Result<()> {
+    self.request_no_content(
+      Method::PUT,
+      &format!("/merchant-skus/{}/status/archive", sku_id),
+      |req| {
+        req.json(&ArchiveSku { is_archived: archive })
+      },
+    ).await
+  }
+
+  /// Creates or updates the SKU record
+  pub async fn upsert_sku(&self, sku_id: &str, sku: &MerchantSku) -> Result<()> {
+    self.request_no_content(
+      Method::PUT,
+      &format!("/merchant-skus/{}", sku_id),
+      |req| {
+        req.json(sku)
+      },
+    ).await
+  }
+
+  pub async fn get_sku(&self, sku_id: &str) -> Result<MerchantSku> {
+    self.request(
+      Method::GET,
+      &format!("/merchant-skus/{}", sku_id),
+      std::convert::identity,
+    ).await
+  }
+
+  pub async fn get_sku_status(&self, sku_id: &str) -> Result<SkuStatus> {
+    self.request(
+      Method::GET,
+      &format!("/merchant-skus/{}/status", sku_id),
+      std::convert::identity,
+    ).await
+  }
+
+  /// The images of a SKU and whether Jet ingested them. Jet has no
+  /// separate image endpoint, this combines `get_sku` and `get_sku_status`.
+  pub async fn get_image_status(&self, sku_id: &str) -> Result<ImageStatus> {
+    let sku = self.get_sku(sku_id).await?;
+    let status = self.get_sku_status(sku_id).await?;
+    Ok(ImageStatus::new(sku, &status))
+  }
+
+  pub async fn update_shipping_exceptions(&self, sku_id: &str, data: &ShippingExceptions) -> Result<()> {
+    self.request_no_content(
+      Method::PUT,
+      &format!("/merchant-skus/{}/shippingexception", sku_id),
+      |req| {
+        req.json(data)
+      },
+    ).await
+  }
+
+  pub async fn get_shipping_exceptions(&self, sku_id: &str) -> Result<ShippingExceptions> {
+    self.request(
+      Method::GET,
+      &format!("/merchant-skus/{}/shippingexception", sku_id),
+      std::convert::identity,
+    ).await
+  }
+
+  /// Sets the child SKUs of the parent SKU `sku_id`
+  pub async fn update_variation(&self, sku_id: &str, relation: &Variation) -> Result<()> {
+    self.request_no_content(
+      Method::PUT,
+      &format!("/merchant-skus/{}/variation", sku_id),
+      |req| {
+        req.json(relation)
+      },
+    ).await
+  }
+
+  pub async fn get_variation(&self, sku_id: &str) -> Result<Variation> {
+    self.request(
+      Method::GET,
+      &format!("/merchant-skus/{}/variation", sku_id),
+      std::convert::identity,
+    ).await
+  }
+
+  pub async fn update_returns_exceptions(&self, sku_id: &str, data: &ReturnsExceptions) -> Result<()> {
+    self.request_no_content(
+      Method::PUT,
+      &format!("/merchant-skus/{}/returnsexception", sku_id),
+      |req| {
+        req.json(data)
+      },
+    ).await
+  }
+
+  pub async fn get_returns_exceptions(&self, sku_id: &str) -> Result<ReturnsExceptions> {
+    self.request(
+      Method::GET,
+      &format!("/merchant-skus/{}/returnsexception", sku_id),
+      std::convert::identity,
+    ).await
+  }
+
+  /// Same as `update_inventory` but only returns the request it would send
+  pub fn preview_update_inventory(&self, sku_id: &str, data: &Inventory) -> Result<PreparedRequest> {
+    self.prepare(
+      Method::PUT,
+      &format!("/merchant-skus/{}/inventory", sku_id),
+      |req| {
+        req.json(data)
+      },
+    )
+  }
+
+  /// Same as `update_price` but only returns the request it would send
+  pub fn preview_update_price(&self, sku_id: &str, data: &PriceUpdate) -> Result<PreparedRequest> {
+    self.prepare(
+      Method::PUT,
+      &format!("/merchant-skus/{}/price", sku_id),
+      |req| {
+        req.json(data)
+      },
+    )
+  }
+
+  /// Same as `archive_sku` but only returns the request it would send
+  pub fn preview_archive_sku(&self, sku_id: &str, archive: bool) -> Result<PreparedRequest> {
+    self.prepare(
+      Method::PUT,
+      &format!("/merchant-skus/{}/status/archive", sku_id),
+      |req| {
+        req.json(&ArchiveSku { is_archived: archive })
+      },
     )
   }
+
+  /// Same as `upsert_sku` but only returns the request it would send
+  pub fn preview_upsert_sku(&self, sku_id: &str, sku: &MerchantSku) -> Result<PreparedRequest> {
+    self.prepare(
+      Method::PUT,
+      &format!("/merchant-skus/{}", sku_id),
+      |req| {
+        req.json(sku)
+      },
+    )
+  }
+}
+
+#[test]
+fn test_unserialize_price_response() {
+  let res: PriceResponse = serde_json::from_str(r#"{
+    "price": 19.99,
+    "fulfillment_nodes": [
+      { "fulfillment_node_id": "b9a4a3b4e3f34b1a9b4b2a1d1c1d1e1f", "fulfillment_node_price": 18.5 }
+    ],
+    "price_last_update": "2017-03-14T12:34:56.123Z"
+  }"#).unwrap();
+  assert_eq!(res.price.to_string(), "19.99");
+  let nodes = res.fulfillment_nodes.unwrap();
+  assert_eq!(nodes[0].fulfillment_node_price.to_string(), "18.5");
+  assert_eq!(
+    res.price_last_update.unwrap().to_rfc3339(),
+    "2017-03-14T12:34:56.123+00:00"
+  );
+}
+
+#[test]
+fn test_unserialize_string_prices() {
+  let res: PriceResponse = serde_json::from_str(r#"{"price":"19.99","fulfillment_nodes":null,"price_last_update":null}"#).unwrap();
+  assert_eq!(res.price.to_string(), "19.99");
+
+  let exception: ShippingException = serde_json::from_str(r#"{
+    "service_level": "Standard",
+    "shipping_method": null,
+    "override_type": "Override charge",
+    "shipping_charge_amount": "4.50",
+    "shipping_exception_type": null
+  }"#).unwrap();
+  assert_eq!(exception.shipping_charge, Some("4.5".parse().unwrap()));
+}
+
+#[test]
+fn test_archive_sku() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+  use mockito::Matcher;
+  use serde_json::json;
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let archive_mock = server
+    .mock("PUT", "/merchant-skus/test_product/status/archive")
+    .match_body(Matcher::Json(json!({ "is_archived": true })))
+    .create();
+
+  get_mock_client(&server).archive_sku("test_product", true).unwrap();
+  archive_mock.assert();
+}
+
+#[test]
+fn test_get_sku() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", "/merchant-skus/test_product")
+    .with_body(r#"{
+      "product_title": "Test Product",
+      "brand": "Ventmere",
+      "multipack_quantity": 1,
+      "standard_product_codes": [{ "standard_product_code": "012345678905", "standard_product_code_type": "UPC" }],
+      "ASIN": "B000000000",
+      "main_image_url": "https://example.com/main.jpg"
+    }"#)
+    .create();
+
+  let sku = get_mock_client(&server).get_sku("test_product").unwrap();
+  assert_eq!(sku.product_title, "Test Product");
+  assert_eq!(sku.asin.as_deref(), Some("B000000000"));
+  assert_eq!(sku.standard_product_codes.unwrap()[0].standard_product_code_type, "UPC");
+}
+
+#[test]
+fn test_get_inventory_bulk() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", mockito::Matcher::Regex("^/merchant-skus/sku_[ac]/inventory$".to_owned()))
+    .with_body(r#"{"fulfillment_nodes":[{"fulfillment_node_id":"node1","quantity":5}]}"#)
+    .expect(2)
+    .create();
+  server
+    .mock("GET", "/merchant-skus/sku_b/inventory")
+    .with_status(404)
+    .create();
+
+  let skus: Vec<String> = ["sku_a", "sku_b", "sku_c"].iter().map(|sku| sku.to_string()).collect();
+  let results = get_mock_client(&server).get_inventory_bulk(&skus);
+  let returned: Vec<&str> = results.iter().map(|(sku, _)| sku.as_str()).collect();
+  assert_eq!(returned, ["sku_a", "sku_b", "sku_c"]);
+  assert_eq!(results[0].1.as_ref().unwrap().fulfillment_nodes[0].quantity, 5);
+  assert!(matches!(results[1].1, Err(Error::NotFound { .. })));
+  assert!(results[2].1.is_ok());
+}
+
+#[test]
+fn test_update_variation() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+  use mockito::Matcher;
+  use serde_json::json;
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let variation_mock = server
+    .mock("PUT", "/merchant-skus/test_shirt/variation")
+    .match_body(Matcher::Json(json!({
+      "relationship": "Variation",
+      "variation_refinements": [101],
+      "children_skus": ["test_shirt_s", "test_shirt_m"],
+      "group_title": "Test Shirt"
+    })))
+    .create();
+
+  get_mock_client(&server)
+    .update_variation("test_shirt", &Variation {
+      relationship: VariationRelationship::Variation,
+      variation_refinements: vec![101],
+      children_skus: vec!["test_shirt_s".to_owned(), "test_shirt_m".to_owned()],
+      group_title: Some("Test Shirt".to_owned()),
+    })
+    .unwrap();
+  variation_mock.assert();
+}
+
+#[test]
+fn test_get_image_status() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", "/merchant-skus/test_product")
+    .with_body(r#"{
+      "product_title": "Test Product",
+      "main_image_url": "https://example.com/main.jpg",
+      "additional_images": ["https://example.com/side.jpg", "https://example.com/back.jpg"]
+    }"#)
+    .create();
+  server
+    .mock("GET", "/merchant-skus/test_product/status")
+    .with_body(r#"{
+      "sku": "test_product",
+      "status": "Missing Listing Data",
+      "sub_status": ["Missing Inventory", "Invalid Swatch Image URL"]
+    }"#)
+    .create();
+
+  let status = get_mock_client(&server).get_image_status("test_product").unwrap();
+  assert_eq!(status.main_image_url.as_deref(), Some("https://example.com/main.jpg"));
+  assert_eq!(status.swatch_image_url, None);
+  assert_eq!(status.additional_images.len(), 2);
+  assert_eq!(status.issues, ["Invalid Swatch Image URL"]);
+  assert!(!status.is_complete());
+}
+
+#[test]
+fn test_unserialize_sku_status() {
+  let status: SkuStatus = serde_json::from_str(r#"{
+    "sku": "test_product",
+    "status": "Missing Listing Data",
+    "sub_status": ["Missing Price", "Missing Inventory"],
+    "sku_last_update": "2017-03-14T12:34:56Z"
+  }"#).unwrap();
+  assert_eq!(status.status, ListingStatus::MissingListingData);
+  assert_eq!(status.sub_status.unwrap().len(), 2);
+
+  let status: ListingStatus = serde_json::from_str(r#""Pending Approval""#).unwrap();
+  assert_eq!(status, ListingStatus::Other("Pending Approval".to_owned()));
+}
+
+#[test]
+fn test_zero_inventory() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+  use mockito::Matcher;
+  use serde_json::json;
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", "/merchant-skus/test_product/inventory")
+    .with_body(r#"{"fulfillment_nodes":[{"fulfillment_node_id":"node1","quantity":5},{"fulfillment_node_id":"node2","quantity":1}]}"#)
+    .create();
+  let all_nodes_mock = server
+    .mock("PUT", "/merchant-skus/test_product/inventory")
+    .match_body(Matcher::Json(json!({
+      "fulfillment_nodes": [
+        { "fulfillment_node_id": "node1", "quantity": 0 },
+        { "fulfillment_node_id": "node2", "quantity": 0 }
+      ]
+    })))
+    .with_status(204)
+    .create();
+  let one_node_mock = server
+    .mock("PUT", "/merchant-skus/other_product/inventory")
+    .match_body(Matcher::Json(json!({
+      "fulfillment_nodes": [{ "fulfillment_node_id": "node3", "quantity": 0 }]
+    })))
+    .with_status(204)
+    .create();
+
+  let client = get_mock_client(&server);
+  client.zero_inventory("test_product", &[]).unwrap();
+  client.zero_inventory("other_product", &["node3".to_owned()]).unwrap();
+  all_nodes_mock.assert();
+  one_node_mock.assert();
+}
+
+#[test]
+fn test_update_inventory() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+  use mockito::Matcher;
+  use serde_json::json;
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let inventory_mock = server
+    .mock("PUT", "/merchant-skus/test_product/inventory")
+    .match_body(Matcher::Json(json!({
+      "fulfillment_nodes": [{ "fulfillment_node_id": "node1", "quantity": 7 }]
+    })))
+    .with_status(204)
+    .with_header("date", "Tue, 14 Mar 2017 12:34:56 GMT")
+    .create();
+
+  let update = get_mock_client(&server)
+    .update_inventory(
+      "test_product",
+      Inventory {
+        fulfillment_nodes: vec![InventoryFulfillmentNode {
+          fulfillment_node_id: "node1".into(),
+          quantity: 7,
+        }],
+      },
+    )
+    .unwrap();
+
+  inventory_mock.assert();
+  assert_eq!(update.merchant_sku, "test_product");
+  assert_eq!(update.inventory.fulfillment_nodes[0].quantity, 7);
+  assert_eq!(update.updated_at.to_rfc3339(), "2017-03-14T12:34:56+00:00");
+}
+
+#[test]
+fn test_update_price_empty_response() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let price_mock = server
+    .mock("PUT", "/merchant-skus/test_product/price")
+    .with_status(200)
+    .with_body("")
+    .create();
+
+  get_mock_client(&server).update_price("test_product", PriceUpdate {
+    price: "19.99".parse().unwrap(),
+    fulfillment_nodes: None,
+  }).unwrap();
+  price_mock.assert();
+}
+
+#[test]
+fn test_price_update_serialize() {
+  use serde_json::json;
+
+  // a number, or a string with the string-prices feature
+  let price = |value: &str| serde_json::to_value(Price { price: value.parse().unwrap() }).unwrap()["price"].clone();
+
+  let base = PriceUpdate::from(Price { price: "19.99".parse().unwrap() });
+  assert_eq!(serde_json::to_value(&base).unwrap(), json!({ "price": price("19.99") }));
+
+  let per_node = PriceUpdate {
+    fulfillment_nodes: Some(vec![NodePrice {
+      fulfillment_node_id: "node1".into(),
+      fulfillment_node_price: "17.5".parse().unwrap(),
+    }]),
+    ..base
+  };
+  assert_eq!(
+    serde_json::to_value(&per_node).unwrap(),
+    json!({
+      "price": price("19.99"),
+      "fulfillment_nodes": [{ "fulfillment_node_id": "node1", "fulfillment_node_price": price("17.5") }]
+    })
+  );
+}
+
+#[test]
+fn test_update_inventory_bulk() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+  use mockito::Matcher;
+  use serde_json::json;
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", "/files/uploadToken")
+    .with_body(json!({ "url": format!("{}/blob/inventory", server.url()), "jet_file_id": "f1" }).to_string())
+    .create();
+  let blob_mock = server
+    .mock("PUT", "/blob/inventory")
+    .match_header("x-ms-blob-type", "BlockBlob")
+    .with_status(201)
+    .create();
+  let uploaded_mock = server
+    .mock("POST", "/files/uploaded")
+    .match_body(Matcher::PartialJson(json!({ "file_type": "Inventory" })))
+    .with_body(r#"{"jet_file_id":"f1","status":"Acknowledged"}"#)
+    .create();
+
+  let handle = get_mock_client(&server)
+    .update_inventory_bulk(&[(
+      "test_product".to_owned(),
+      Inventory {
+        fulfillment_nodes: vec![InventoryFulfillmentNode {
+          fulfillment_node_id: "node1".into(),
+          quantity: 5,
+        }],
+      },
+    )])
+    .unwrap();
+
+  blob_mock.assert();
+  uploaded_mock.assert();
+  assert_eq!(handle.jet_file_id, "f1");
 }
@@ -0,0 +1,62 @@
+//! Token bucket limiting how fast a client sends requests
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub(crate) struct RateLimiter {
+  /// Permits added per second
+  rate: f64,
+  /// Most permits that can accumulate, allowing short bursts
+  capacity: f64,
+  state: Mutex<State>,
+}
+
+struct State {
+  permits: f64,
+  updated: Instant,
+}
+
+impl RateLimiter {
+  pub(crate) fn new(requests_per_second: f64) -> RateLimiter {
+    let capacity = requests_per_second.max(1.0);
+    RateLimiter {
+      rate: requests_per_second,
+      capacity,
+      state: Mutex::new(State {
+        permits: capacity,
+        updated: Instant::now(),
+      }),
+    }
+  }
+
+  /// Takes a permit and returns how long the caller must wait before using it
+  pub(crate) fn reserve(&self) -> Duration {
+    let mut state = self.state.lock().expect("lock rate limiter");
+    let now = Instant::now();
+    let refill = now.duration_since(state.updated).as_secs_f64() * self.rate;
+    state.permits = (state.permits + refill).min(self.capacity) - 1.0;
+    state.updated = now;
+    if state.permits >= 0.0 {
+      Duration::ZERO
+    } else {
+      Duration::from_secs_f64(-state.permits / self.rate)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_reserve() {
+    let limiter = RateLimiter::new(2.0);
+    assert_eq!(limiter.reserve(), Duration::ZERO);
+    assert_eq!(limiter.reserve(), Duration::ZERO);
+
+    let wait = limiter.reserve();
+    assert!(wait > Duration::from_millis(400) && wait <= Duration::from_millis(500), "{:?}", wait);
+    let wait = limiter.reserve();
+    assert!(wait > Duration::from_millis(900) && wait <= Duration::from_millis(1000), "{:?}", wait);
+  }
+}
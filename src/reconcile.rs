@@ -0,0 +1,148 @@
+//! Audits settlement reports against the orders they pay out
+//!
+//! Enable the `decimal` feature for exact amounts, with the default `f64`
+//! money type differences below half a cent are ignored.
+
+use crate::money::{is_zero_amount, Money};
+use crate::orders::Order;
+use crate::settlement::{SettlementOrder, SettlementReport};
+
+/// Amounts of a single order, either as settled by Jet or as expected from
+/// the order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SettlementAmounts {
+  pub merchant_price: Money,
+  pub tax: Money,
+  pub shipping_revenue: Money,
+  pub shipping_tax: Money,
+  /// Commissions and other fees Jet charged
+  pub fees: Money,
+}
+
+impl SettlementAmounts {
+  /// Amounts expected for `order`, summed from its items
+  pub fn expected(order: &Order) -> Self {
+    let totals = order.computed_totals();
+    let price = totals.item_price.as_ref();
+    SettlementAmounts {
+      merchant_price: price.map(|price| price.base_price).unwrap_or_default(),
+      tax: price.and_then(|price| price.item_tax).unwrap_or_default(),
+      shipping_revenue: price.map(|price| price.item_shipping_cost).unwrap_or_default(),
+      shipping_tax: price.and_then(|price| price.item_shipping_tax).unwrap_or_default(),
+      fees: totals.item_fees.unwrap_or_default()
+        + totals.fee_adjustments.iter().flatten().map(|fee| fee.value).sum::<Money>(),
+    }
+  }
+
+  /// Amounts Jet settled, missing amounts count as zero
+  pub fn settled(order: &SettlementOrder) -> Self {
+    SettlementAmounts {
+      merchant_price: order.merchant_price,
+      tax: order.tax.unwrap_or_default(),
+      shipping_revenue: order.shipping_revenue.unwrap_or_default(),
+      shipping_tax: order.shipping_tax.unwrap_or_default(),
+      fees: order.fee_adjustments.iter().flatten().map(|fee| fee.value).sum(),
+    }
+  }
+
+  /// Whether both have the same amounts
+  pub fn matches(&self, other: &SettlementAmounts) -> bool {
+    is_zero_amount(self.merchant_price - other.merchant_price)
+      && is_zero_amount(self.tax - other.tax)
+      && is_zero_amount(self.shipping_revenue - other.shipping_revenue)
+      && is_zero_amount(self.shipping_tax - other.shipping_tax)
+      && is_zero_amount(self.fees - other.fees)
+  }
+}
+
+/// A settled order that doesn't match what was expected
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettlementDiscrepancy {
+  /// None of the given orders has the settled merchant_order_id
+  MissingOrder {
+    merchant_order_id: String,
+    settled: SettlementAmounts,
+  },
+  /// The settled amounts differ from the amounts of the order
+  Mismatch {
+    merchant_order_id: String,
+    expected: SettlementAmounts,
+    settled: SettlementAmounts,
+  },
+}
+
+/// Joins the orders of `report` to `orders` by merchant_order_id and
+/// returns the ones that didn't settle as expected, in report order
+pub fn reconcile_settlement(report: &SettlementReport, orders: &[Order]) -> Vec<SettlementDiscrepancy> {
+  report
+    .orders
+    .iter()
+    .filter_map(|settled_order| {
+      let merchant_order_id = settled_order.merchant_order_id.clone();
+      let settled = SettlementAmounts::settled(settled_order);
+      let order = orders
+        .iter()
+        .find(|order| order.merchant_order_id == *settled_order.merchant_order_id);
+      match order {
+        Some(order) => {
+          let expected = SettlementAmounts::expected(order);
+          if expected.matches(&settled) {
+            None
+          } else {
+            Some(SettlementDiscrepancy::Mismatch { merchant_order_id, expected, settled })
+          }
+        }
+        None => Some(SettlementDiscrepancy::MissingOrder { merchant_order_id, settled }),
+      }
+    })
+    .collect()
+}
+
+#[test]
+fn test_reconcile_settlement() {
+  let order = crate::orders::get_test_order();
+  let report: SettlementReport = serde_json::from_str(r#"{
+    "settlement_report_id": "s1",
+    "settlement_state": "Closed",
+    "currency": "USD",
+    "settlement_period_start": "2017-03-01T00:00:00Z",
+    "settlement_period_end": "2017-03-15T00:00:00Z",
+    "order_balance": 0,
+    "return_balance": 0,
+    "jet_adjustment": 0,
+    "settlement_value": 0,
+    "orders": [
+      {
+        "merchant_order_id": "2ab4c8b414124f0fa04072d615ec0610",
+        "merchant_price": 54.97,
+        "tax": 3.64,
+        "shipping_revenue": 5.0,
+        "shipping_tax": 0.33,
+        "fee_adjustments": [
+          { "adjustment_name": "Commission", "adjustment_type": "Commission", "commission_id": "c1", "value": 8.25 }
+        ]
+      },
+      { "merchant_order_id": "d2a8d7e0b1f94c2a9b3e6f5c4d3b2a19", "merchant_price": 10.0 }
+    ]
+  }"#).unwrap();
+
+  let discrepancies = reconcile_settlement(&report, &[order]);
+  assert_eq!(discrepancies.len(), 1);
+  match discrepancies[0] {
+    SettlementDiscrepancy::MissingOrder { ref merchant_order_id, ref settled } => {
+      assert_eq!(merchant_order_id, "d2a8d7e0b1f94c2a9b3e6f5c4d3b2a19");
+      assert_eq!(settled.merchant_price.to_string(), "10");
+    }
+    ref other => panic!("unexpected discrepancy: {:?}", other),
+  }
+
+  let mut order = crate::orders::get_test_order();
+  order.order_items[1].item_price.base_price = order.order_items[0].item_price.base_price;
+  match reconcile_settlement(&report, &[order])[0] {
+    SettlementDiscrepancy::Mismatch { ref expected, ref settled, .. } => {
+      assert_eq!(expected.merchant_price.to_string(), "79.96");
+      assert_eq!(settled.merchant_price.to_string(), "54.97");
+    }
+    ref other => panic!("unexpected discrepancy: {:?}", other),
+  }
+}
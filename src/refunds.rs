@@ -0,0 +1,123 @@
+//! Implements Refunds API
+//! [Jet Documentation](https://developer.jet.com/docs/refunds)
+//!
+
+#[cfg(feature = "async")]
+use super::async_client::AsyncClient;
+use super::client::{Client, Method};
+use crate::error::*;
+pub use crate::returns::RefundAmount;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefundItem {
+  pub merchant_sku: String,
+  /// Number of units being refunded
+  pub refund_quantity: i32,
+  pub refund_reason: Option<String>,
+  pub refund_feedback: Option<String>,
+  /// Amounts to refund, including any shipping refund
+  pub refund_amount: Option<RefundAmount>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateRefund {
+  /// Optional merchant supplied refund ID
+  pub alt_refund_id: Option<String>,
+  pub items: Vec<RefundItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRefundResponse {
+  /// Jet's unique ID for the refund, used with `get_refund`
+  pub refund_authorization_id: String,
+  pub refund_status: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Refund {
+  pub refund_authorization_id: String,
+  pub alt_refund_id: Option<String>,
+  pub merchant_order_id: Option<String>,
+  /// One of 'created', 'processing', 'accepted' or 'rejected'
+  pub refund_status: String,
+  pub items: Option<Vec<RefundItem>>,
+}
+
+impl Client {
+  /// Always sent, `ClientOptions::dedupe_window` doesn't cover refunds
+  pub fn create_refund(&self, order_id: &str, refund: &CreateRefund) -> Result<CreateRefundResponse> {
+    self.request(
+      Method::POST,
+      &format!("/refunds/{}", order_id),
+      |req| {
+        req.json(refund)
+      },
+    )
+  }
+
+  pub fn get_refund(&self, refund_id: &str) -> Result<Refund> {
+    self.request(
+      Method::GET,
+      &format!("/refunds/state/{}", refund_id),
+      std::convert::identity,
+    )
+  }
+}
+
+#[cfg(feature = "async")]
+impl AsyncClient {
+  /// Always sent, `ClientOptions::dedupe_window` doesn't cover refunds
+  pub async fn create_refund(&self, order_id: &str, refund: &CreateRefund) -> Result<CreateRefundResponse> {
+    self.request(
+      Method::POST,
+      &format!("/refunds/{}", order_id),
+      |req| {
+        req.json(refund)
+      },
+    ).await
+  }
+
+  pub async fn get_refund(&self, refund_id: &str) -> Result<Refund> {
+    self.request(
+      Method::GET,
+      &format!("/refunds/state/{}", refund_id),
+      std::convert::identity,
+    ).await
+  }
+}
+
+#[test]
+fn test_create_refund() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+  use mockito::Matcher;
+  use serde_json::json;
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let refund_mock = server
+    .mock("POST", "/refunds/2ab4c8b414124f0fa04072d615ec0610")
+    .match_body(Matcher::PartialJson(json!({
+      "items": [{ "merchant_sku": "test_product", "refund_quantity": 1 }]
+    })))
+    .with_body(r#"{"refund_authorization_id":"f2b1c3d4","refund_status":"created"}"#)
+    .create();
+
+  let res = get_mock_client(&server)
+    .create_refund(
+      "2ab4c8b414124f0fa04072d615ec0610",
+      &CreateRefund {
+        alt_refund_id: None,
+        items: vec![RefundItem {
+          merchant_sku: "test_product".to_owned(),
+          refund_quantity: 1,
+          refund_reason: Some("damaged".to_owned()),
+          refund_feedback: None,
+          refund_amount: None,
+        }],
+      },
+    )
+    .unwrap();
+
+  refund_mock.assert();
+  assert_eq!(res.refund_authorization_id, "f2b1c3d4");
+}
@@ -0,0 +1,105 @@
+//! Backoff policy shared by the blocking and async clients
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// Statuses worth retrying: throttling and transient server failures
+pub(crate) fn is_transient(status: StatusCode) -> bool {
+  matches!(
+    status,
+    StatusCode::TOO_MANY_REQUESTS
+      | StatusCode::INTERNAL_SERVER_ERROR
+      | StatusCode::BAD_GATEWAY
+      | StatusCode::SERVICE_UNAVAILABLE
+      | StatusCode::GATEWAY_TIMEOUT
+  )
+}
+
+/// Parses `Retry-After` as either delay-seconds or an HTTP-date
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+  let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+  if let Ok(secs) = value.parse::<u64>() {
+    return Some(Duration::from_secs(secs));
+  }
+  let at = DateTime::parse_from_rfc2822(value).ok()?.with_timezone(&Utc);
+  Some((at - Utc::now()).to_std().unwrap_or_default())
+}
+
+/// Exponential backoff: `base * 2^attempt`
+pub(crate) fn backoff(base: Duration, attempt: u32) -> Duration {
+  base.saturating_mul(2u32.saturating_pow(attempt))
+}
+
+/// Full jitter: a uniformly random duration between zero and `max`, so
+/// clients retrying at the same time spread out
+pub(crate) fn jitter(max: Duration) -> Duration {
+  // RandomState is seeded randomly, which is enough randomness for spacing
+  // out retries without pulling in a dependency
+  let random = RandomState::new().build_hasher().finish();
+  max.mul_f64(random as f64 / u64::MAX as f64)
+}
+
+/// Delay before the next attempt: the server's `Retry-After` if sent,
+/// otherwise `jitter(backoff(base, attempt))`
+pub(crate) fn delay(headers: &HeaderMap, base: Duration, attempt: u32) -> Duration {
+  retry_after(headers).unwrap_or_else(|| jitter(backoff(base, attempt)))
+}
+
+/// Whether waiting `delay` more keeps the retries of a request, started
+/// `elapsed` ago, within `max_total`
+pub(crate) fn within_budget(elapsed: Duration, delay: Duration, max_total: Option<Duration>) -> bool {
+  max_total.is_none_or(|max_total| elapsed.saturating_add(delay) <= max_total)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use reqwest::header::HeaderValue;
+
+  #[test]
+  fn test_is_transient() {
+    assert!(is_transient(StatusCode::TOO_MANY_REQUESTS));
+    assert!(is_transient(StatusCode::SERVICE_UNAVAILABLE));
+    assert!(!is_transient(StatusCode::BAD_REQUEST));
+    assert!(!is_transient(StatusCode::NOT_IMPLEMENTED));
+  }
+
+  #[test]
+  fn test_delay() {
+    let base = Duration::from_millis(100);
+    let mut headers = HeaderMap::new();
+    assert_eq!(backoff(base, 0), Duration::from_millis(100));
+    assert_eq!(backoff(base, 3), Duration::from_millis(800));
+    for _ in 0..20 {
+      assert!(delay(&headers, base, 0) <= Duration::from_millis(100));
+      assert!(delay(&headers, base, 3) <= Duration::from_millis(800));
+    }
+
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+    assert_eq!(delay(&headers, base, 3), Duration::from_secs(7));
+
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("Sun, 06 Nov 1994 08:49:37 GMT"));
+    assert_eq!(delay(&headers, base, 3), Duration::from_secs(0));
+  }
+
+  #[test]
+  fn test_jitter() {
+    let max = Duration::from_secs(10);
+    let delays: Vec<Duration> = (0..20).map(|_| jitter(max)).collect();
+    assert!(delays.iter().all(|delay| *delay <= max));
+    assert!(delays.iter().any(|delay| *delay != delays[0]));
+    assert_eq!(jitter(Duration::from_secs(0)), Duration::from_secs(0));
+  }
+
+  #[test]
+  fn test_within_budget() {
+    let second = Duration::from_secs(1);
+    assert!(within_budget(second * 100, second, None));
+    assert!(within_budget(second, second, Some(second * 2)));
+    assert!(!within_budget(second * 2, second, Some(second * 2)));
+  }
+}
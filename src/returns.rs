@@ -0,0 +1,147 @@
+//! Implements Returns API
+//! [Jet Documentation](https://developer.jet.com/docs/returns)
+//!
+
+#[cfg(feature = "async")]
+use super::async_client::AsyncClient;
+use super::client::{Client, Method};
+use chrono::{DateTime, Utc};
+use crate::error::*;
+use crate::money::{deserialize_price, serialize_price, Money};
+
+/// Amounts refunded to the customer for a returned item
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefundAmount {
+  #[serde(serialize_with = "serialize_price", deserialize_with = "deserialize_price")]
+  pub principal: Money,
+  #[serde(serialize_with = "serialize_price", deserialize_with = "deserialize_price")]
+  pub tax: Money,
+  #[serde(serialize_with = "serialize_price", deserialize_with = "deserialize_price")]
+  pub shipping_cost: Money,
+  #[serde(serialize_with = "serialize_price", deserialize_with = "deserialize_price")]
+  pub shipping_tax: Money,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReturnItem {
+  pub order_item_id: String,
+  pub alt_order_item_id: Option<String>,
+  pub merchant_sku: String,
+  pub return_quantity: i32,
+  /// Reason the customer gave for the return
+  pub reason: Option<String>,
+  pub refund_amount: Option<RefundAmount>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Return {
+  /// Jet's unique ID for a given merchant return.
+  pub merchant_return_authorization_id: String,
+  /// Jet's human readable return ID number.
+  pub reference_return_authorization_id: Option<String>,
+  /// The merchant order the return belongs to.
+  pub merchant_order_id: String,
+  pub reference_order_id: Option<String>,
+  pub alt_order_id: Option<String>,
+  pub return_status: Option<String>,
+  pub return_date: Option<DateTime<Utc>>,
+  pub refund_without_return: Option<bool>,
+  pub shipping_carrier: Option<String>,
+  pub tracking_number: Option<String>,
+  #[serde(rename = "return_merchant_SKUs")]
+  pub return_merchant_skus: Vec<ReturnItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetReturnsResponse {
+  pub return_urls: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompleteReturnItem {
+  pub order_item_id: String,
+  /// Number of units received back from the customer
+  pub total_quantity_returned: i32,
+  /// Number of units the customer is refunded for
+  pub order_return_refund_qty: i32,
+  pub return_refund_feedback: Option<String>,
+  pub refund_amount: RefundAmount,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompleteReturn {
+  pub merchant_order_id: String,
+  pub alt_order_id: Option<String>,
+  /// Whether the merchant agrees to pay the return shipping charge
+  pub agree_to_return_charge: bool,
+  pub items: Vec<CompleteReturnItem>,
+}
+
+impl Client {
+  /// Lists returns created by customers and awaiting the merchant
+  pub fn get_returns(&self) -> Result<GetReturnsResponse> {
+    self.request(Method::GET, "/returns/created", std::convert::identity)
+  }
+
+  pub fn get_return_detail(&self, return_url: &str) -> Result<Return> {
+    self.request(Method::GET, return_url, std::convert::identity)
+  }
+
+  pub fn complete_return(&self, return_id: &str, body: &CompleteReturn) -> Result<()> {
+    self.request_no_content(
+      Method::PUT,
+      &format!("/returns/{}/complete", return_id),
+      |req| {
+        req.json(body)
+      },
+    )
+  }
+}
+
+#[cfg(feature = "async")]
+impl AsyncClient {
+  /// Lists returns created by customers and awaiting the merchant
+  pub async fn get_returns(&self) -> Result<GetReturnsResponse> {
+    self.request(Method::GET, "/returns/created", std::convert::identity).await
+  }
+
+  pub async fn get_return_detail(&self, return_url: &str) -> Result<Return> {
+    self.request(Method::GET, return_url, std::convert::identity).await
+  }
+
+  pub async fn complete_return(&self, return_id: &str, body: &CompleteReturn) -> Result<()> {
+    self.request_no_content(
+      Method::PUT,
+      &format!("/returns/{}/complete", return_id),
+      |req| {
+        req.json(body)
+      },
+    ).await
+  }
+}
+
+#[test]
+fn test_unserialize_return() {
+  let ret: Return = serde_json::from_str(r#"{
+    "merchant_return_authorization_id": "6c1c3b6e1a0b4c5e8f7d9a2b3c4d5e6f",
+    "reference_return_authorization_id": "100234567",
+    "merchant_order_id": "2ab4c8b414124f0fa04072d615ec0610",
+    "reference_order_id": "401234567",
+    "alt_order_id": null,
+    "return_status": "created",
+    "return_date": "2017-03-14T12:34:56Z",
+    "refund_without_return": false,
+    "shipping_carrier": "UPS",
+    "tracking_number": "1Z12342452342",
+    "return_merchant_SKUs": [{
+      "order_item_id": "2906d22b212d4745ab9986b80b1ad2af",
+      "alt_order_item_id": null,
+      "merchant_sku": "test_product",
+      "return_quantity": 1,
+      "reason": "damaged",
+      "refund_amount": { "principal": 19.99, "tax": 1.5, "shipping_cost": 0, "shipping_tax": 0 }
+    }]
+  }"#).unwrap();
+  assert_eq!(ret.return_merchant_skus.len(), 1);
+  assert_eq!(ret.return_merchant_skus[0].merchant_sku, "test_product");
+}
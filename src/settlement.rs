@@ -0,0 +1,120 @@
+//! Implements Settlement API
+//! [Jet Documentation](https://developer.jet.com/docs/settlement)
+//!
+
+#[cfg(feature = "async")]
+use super::async_client::AsyncClient;
+use super::client::{Client, Method};
+use chrono::{DateTime, Utc};
+use crate::error::*;
+use crate::money::Money;
+pub use crate::orders::{AdjustmentType, FeeAdjustment};
+
+/// Amounts settled for a single order
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SettlementOrder {
+  pub merchant_order_id: String,
+  pub reference_order_id: Option<String>,
+  pub merchant_price: Money,
+  pub tax: Option<Money>,
+  pub shipping_revenue: Option<Money>,
+  pub shipping_tax: Option<Money>,
+  /// Commissions and other fees Jet charged on the order
+  pub fee_adjustments: Option<Vec<FeeAdjustment>>,
+}
+
+/// A fee charged outside of any single order
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SettlementFee {
+  pub fee_name: String,
+  pub fee_type: Option<String>,
+  pub amount: Money,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SettlementReport {
+  pub settlement_report_id: String,
+  /// 'Open' while the period is accruing, 'Closed' once paid out
+  pub settlement_state: String,
+  pub currency: Option<String>,
+  pub settlement_period_start: DateTime<Utc>,
+  pub settlement_period_end: DateTime<Utc>,
+  pub order_balance: Money,
+  pub return_balance: Money,
+  pub jet_adjustment: Money,
+  /// Total paid out to the merchant for the period
+  pub settlement_value: Money,
+  pub orders: Vec<SettlementOrder>,
+  pub fees: Option<Vec<SettlementFee>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GetSettlementReportsResponse {
+  settlement_report_urls: Vec<String>,
+}
+
+impl GetSettlementReportsResponse {
+  fn into_ids(self) -> Vec<String> {
+    self
+      .settlement_report_urls
+      .into_iter()
+      .filter_map(|url| url.trim_end_matches('/').rsplit('/').next().map(ToOwned::to_owned))
+      .collect()
+  }
+}
+
+impl Client {
+  /// IDs of the settlement reports from the last `days` days
+  pub fn get_settlement_report_ids(&self, days: u32) -> Result<Vec<String>> {
+    let res: GetSettlementReportsResponse = self.request(
+      Method::GET,
+      &format!("/settlement/{}", days),
+      std::convert::identity,
+    )?;
+    Ok(res.into_ids())
+  }
+
+  pub fn get_settlement_report(&self, settlement_id: &str) -> Result<SettlementReport> {
+    self.request(
+      Method::GET,
+      &format!("/settlement/report/{}", settlement_id),
+      std::convert::identity,
+    )
+  }
+}
+
+#[cfg(feature = "async")]
+impl AsyncClient {
+  /// IDs of the settlement reports from the last `days` days
+  pub async fn get_settlement_report_ids(&self, days: u32) -> Result<Vec<String>> {
+    let res: GetSettlementReportsResponse = self.request(
+      Method::GET,
+      &format!("/settlement/{}", days),
+      std::convert::identity,
+    ).await?;
+    Ok(res.into_ids())
+  }
+
+  pub async fn get_settlement_report(&self, settlement_id: &str) -> Result<SettlementReport> {
+    self.request(
+      Method::GET,
+      &format!("/settlement/report/{}", settlement_id),
+      std::convert::identity,
+    ).await
+  }
+}
+
+#[test]
+fn test_get_settlement_report_ids() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", "/settlement/7")
+    .with_body(r#"{"settlement_report_urls":["/settlement/report/s1","/settlement/report/s2"]}"#)
+    .create();
+
+  let ids = get_mock_client(&server).get_settlement_report_ids(7).unwrap();
+  assert_eq!(ids, ["s1", "s2"]);
+}
@@ -0,0 +1,127 @@
+//! Implements merchant sales tax settings
+//! [Jet Documentation](https://developer.jet.com/docs/taxes)
+//!
+
+#[cfg(feature = "async")]
+use super::async_client::AsyncClient;
+use super::client::{Client, Method};
+use crate::error::*;
+use crate::money::Money;
+
+/// A state where the merchant has sales tax nexus
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NexusState {
+  /// Two letter state code, e.g. 'NY'
+  pub state: String,
+  /// Whether Jet should collect sales tax on the merchant's behalf
+  pub collect_tax: bool,
+  /// Merchant specified rate used instead of Jet's calculated rate
+  pub tax_rate_override: Option<Money>,
+}
+
+/// Tax treatment of a product tax code, optionally limited to one state
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaxRule {
+  pub tax_code: String,
+  pub state: Option<String>,
+  pub exempt: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaxSettings {
+  pub nexus_states: Vec<NexusState>,
+  pub tax_rules: Option<Vec<TaxRule>>,
+}
+
+impl Client {
+  pub fn get_tax_settings(&self) -> Result<TaxSettings> {
+    self.request(Method::GET, "/taxes/settings", std::convert::identity)
+  }
+
+  pub fn update_tax_settings(&self, settings: &TaxSettings) -> Result<()> {
+    self.request_no_content(
+      Method::PUT,
+      "/taxes/settings",
+      |req| {
+        req.json(settings)
+      },
+    )
+  }
+}
+
+#[cfg(feature = "async")]
+impl AsyncClient {
+  pub async fn get_tax_settings(&self) -> Result<TaxSettings> {
+    self.request(Method::GET, "/taxes/settings", std::convert::identity).await
+  }
+
+  pub async fn update_tax_settings(&self, settings: &TaxSettings) -> Result<()> {
+    self.request_no_content(
+      Method::PUT,
+      "/taxes/settings",
+      |req| {
+        req.json(settings)
+      },
+    ).await
+  }
+}
+
+#[test]
+fn test_get_tax_settings() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  server
+    .mock("GET", "/taxes/settings")
+    .with_body(r#"{
+      "nexus_states": [
+        { "state": "NY", "collect_tax": true, "tax_rate_override": null },
+        { "state": "NJ", "collect_tax": false, "tax_rate_override": 0.06625 }
+      ],
+      "tax_rules": [{ "tax_code": "4000000", "state": "NY", "exempt": true }]
+    }"#)
+    .create();
+
+  let settings = get_mock_client(&server).get_tax_settings().unwrap();
+  assert_eq!(settings.nexus_states.len(), 2);
+  assert_eq!(settings.nexus_states[0].state, "NY");
+  assert!(settings.nexus_states[0].collect_tax);
+  assert!(settings.nexus_states[0].tax_rate_override.is_none());
+  assert!(settings.nexus_states[1].tax_rate_override.is_some());
+  let rules = settings.tax_rules.unwrap();
+  assert_eq!(rules[0].tax_code, "4000000");
+  assert_eq!(rules[0].state.as_deref(), Some("NY"));
+  assert!(rules[0].exempt);
+}
+
+#[test]
+fn test_update_tax_settings() {
+  use crate::client::{get_mock_client, MOCK_TOKEN};
+  use mockito::Matcher;
+  use serde_json::json;
+
+  let mut server = mockito::Server::new();
+  server.mock("POST", "/token").with_body(MOCK_TOKEN).create();
+  let update_mock = server
+    .mock("PUT", "/taxes/settings")
+    .match_body(Matcher::Json(json!({
+      "nexus_states": [{ "state": "NY", "collect_tax": true, "tax_rate_override": null }],
+      "tax_rules": null
+    })))
+    .with_status(204)
+    .create();
+
+  get_mock_client(&server)
+    .update_tax_settings(&TaxSettings {
+      nexus_states: vec![NexusState {
+        state: "NY".to_owned(),
+        collect_tax: true,
+        tax_rate_override: None,
+      }],
+      tax_rules: None,
+    })
+    .unwrap();
+
+  update_mock.assert();
+}
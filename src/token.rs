@@ -0,0 +1,185 @@
+//! Auth token caching
+//!
+//! Clients keep the bearer token in a [`TokenStore`]. The default
+//! [`InMemoryTokenStore`] lives as long as the client, while
+//! [`FileTokenStore`] lets short-lived processes reuse a still-valid token.
+
+use chrono::{DateTime, Duration, Utc};
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Token {
+  pub(crate) id_token: String,
+  pub(crate) token_type: String,
+  pub(crate) expires_on: DateTime<Utc>,
+}
+
+/// The bearer token itself is redacted
+impl fmt::Debug for Token {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("Token")
+      .field("id_token", &"<redacted>")
+      .field("token_type", &self.token_type)
+      .field("expires_on", &self.expires_on)
+      .finish()
+  }
+}
+
+impl Token {
+  /// A token is reused until 15 minutes before it expires
+  pub(crate) fn is_fresh(&self) -> bool {
+    self.expires_on - Duration::minutes(15) >= Utc::now()
+  }
+}
+
+pub trait TokenStore: Send + Sync {
+  /// Returns the last saved token, if any. Expired tokens may be returned,
+  /// the client checks expiry itself.
+  fn load(&self) -> Option<Token>;
+
+  /// Persists a newly fetched token
+  fn save(&self, token: &Token);
+}
+
+/// Keeps the token in memory for the lifetime of the store
+#[derive(Debug, Default)]
+pub struct InMemoryTokenStore {
+  token: RwLock<Option<Token>>,
+}
+
+impl InMemoryTokenStore {
+  pub fn new() -> InMemoryTokenStore {
+    InMemoryTokenStore::default()
+  }
+}
+
+impl TokenStore for InMemoryTokenStore {
+  fn load(&self) -> Option<Token> {
+    self.token.read().expect("read token").clone()
+  }
+
+  fn save(&self, token: &Token) {
+    self.token.write().expect("write token").replace(token.clone());
+  }
+}
+
+/// Persists the token as JSON at `path`, caching it in memory after the
+/// first read.
+///
+/// Saving is best effort: if the file can't be written the token is still
+/// kept in memory. On unix the file is only readable by its owner (0600).
+#[derive(Debug)]
+pub struct FileTokenStore {
+  path: PathBuf,
+  cache: InMemoryTokenStore,
+}
+
+impl FileTokenStore {
+  pub fn new<P: Into<PathBuf>>(path: P) -> FileTokenStore {
+    FileTokenStore {
+      path: path.into(),
+      cache: InMemoryTokenStore::new(),
+    }
+  }
+}
+
+impl TokenStore for FileTokenStore {
+  fn load(&self) -> Option<Token> {
+    if let Some(token) = self.cache.load() {
+      return Some(token);
+    }
+    let token: Token = serde_json::from_slice(&fs::read(&self.path).ok()?).ok()?;
+    self.cache.save(&token);
+    Some(token)
+  }
+
+  fn save(&self, token: &Token) {
+    self.cache.save(token);
+    if let Ok(data) = serde_json::to_vec(token) {
+      write_private(&self.path, &data).ok();
+    }
+  }
+}
+
+/// Writes `data` to a temporary file only the owner can read, then renames
+/// it over `path` so the token is never readable by others, even briefly
+fn write_private(path: &Path, data: &[u8]) -> io::Result<()> {
+  let mut tmp = path.as_os_str().to_owned();
+  tmp.push(".tmp");
+  let tmp = PathBuf::from(tmp);
+  // a leftover temp file would keep its old permissions
+  fs::remove_file(&tmp).ok();
+
+  let mut opts = fs::OpenOptions::new();
+  opts.write(true).create_new(true);
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::OpenOptionsExt;
+    opts.mode(0o600);
+  }
+  let mut file = opts.open(&tmp)?;
+  file.write_all(data)?;
+  drop(file);
+  fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_file_token_store() {
+    let path = std::env::temp_dir().join(format!("jet-token-{}.json", std::process::id()));
+    let token = Token {
+      id_token: "file-token".to_owned(),
+      token_type: "Bearer".to_owned(),
+      expires_on: Utc::now() + Duration::hours(1),
+    };
+
+    FileTokenStore::new(&path).save(&token);
+
+    let loaded = FileTokenStore::new(&path).load().unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.id_token, "file-token");
+    assert!(loaded.is_fresh());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_file_token_store_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = std::env::temp_dir().join(format!("jet-token-mode-{}.json", std::process::id()));
+    fs::write(&path, b"{}").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+    FileTokenStore::new(&path).save(&Token {
+      id_token: "file-token".to_owned(),
+      token_type: "Bearer".to_owned(),
+      expires_on: Utc::now() + Duration::hours(1),
+    });
+
+    let mode = fs::metadata(&path).unwrap().permissions().mode();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(mode & 0o777, 0o600);
+  }
+
+  #[test]
+  fn test_token_debug_redacted() {
+    let token = Token {
+      id_token: "secret-token".to_owned(),
+      token_type: "Bearer".to_owned(),
+      expires_on: Utc::now(),
+    };
+    let store = InMemoryTokenStore::new();
+    store.save(&token);
+    for debug in [format!("{:?}", token), format!("{:?}", store)] {
+      assert!(!debug.contains("secret-token"), "{}", debug);
+      assert!(debug.contains("<redacted>"), "{}", debug);
+    }
+  }
+}
@@ -0,0 +1,38 @@
+//! Request logging, compiled in with the `tracing` feature
+//!
+//! Only the method, path, status and timing are recorded. Credentials and
+//! tokens are never logged.
+
+use reqwest::{Method, StatusCode};
+use std::time::Duration;
+
+#[allow(unused_variables)]
+pub(crate) fn response(method: &Method, path: &str, status: StatusCode, elapsed: Duration, attempt: u32) {
+  #[cfg(feature = "tracing")]
+  tracing::debug!(
+    method = %method,
+    path,
+    status = status.as_u16(),
+    elapsed_ms = elapsed.as_millis() as u64,
+    attempt,
+    "jet response"
+  );
+}
+
+#[allow(unused_variables)]
+pub(crate) fn send_error(method: &Method, path: &str, error: &reqwest::Error, elapsed: Duration) {
+  #[cfg(feature = "tracing")]
+  tracing::warn!(
+    method = %method,
+    path,
+    error = %error,
+    elapsed_ms = elapsed.as_millis() as u64,
+    "jet request failed"
+  );
+}
+
+#[allow(unused_variables)]
+pub(crate) fn unparsed_order_url(url: &str) {
+  #[cfg(feature = "tracing")]
+  tracing::warn!(url, "skipping order url without a merchant_order_id");
+}
@@ -1,8 +1,104 @@
+use serde::de::{Deserialize, Deserializer, Error as _};
 use serde::ser::Serializer;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 
 // "Date is expected to be in ISO 8601 format yyyy-MM-ddTHH:mm:ss.fffffff-HH:MM"
 pub fn serialize_datetime<S>(value: &DateTime<Utc>, ser: S) -> Result<S::Ok, S::Error> where S: Serializer {
   let as_str = format!("{}", value.format("%Y-%m-%dT%H:%M:%S.0000000-00:00"));
   ser.serialize_str(&as_str)
-}
\ No newline at end of file
+}
+
+/// Parses RFC 3339 timestamps, and ones without an offset such as
+/// `2017-03-14T12:34:56.7654321`, which are assumed to be UTC
+pub(crate) fn parse_datetime(value: &str) -> Option<DateTime<Utc>> {
+  if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+    return Some(dt.with_timezone(&Utc));
+  }
+  NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+    .ok()
+    .map(|dt| dt.and_utc())
+}
+
+pub fn deserialize_datetime<'de, D>(de: D) -> Result<DateTime<Utc>, D::Error> where D: Deserializer<'de> {
+  let value = String::deserialize(de)?;
+  parse_datetime(&value).ok_or_else(|| D::Error::custom(format!("invalid datetime: '{}'", value)))
+}
+
+pub fn deserialize_datetime_opt<'de, D>(de: D) -> Result<Option<DateTime<Utc>>, D::Error> where D: Deserializer<'de> {
+  match Option::<String>::deserialize(de)? {
+    Some(value) => parse_datetime(&value)
+      .map(Some)
+      .ok_or_else(|| D::Error::custom(format!("invalid datetime: '{}'", value))),
+    None => Ok(None),
+  }
+}
+
+/// Percent-encodes everything but unreserved characters so `value` can be
+/// used as a single URL path segment
+pub(crate) fn encode_path_segment(value: &str) -> String {
+  let mut encoded = String::with_capacity(value.len());
+  for byte in value.bytes() {
+    match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+      _ => encoded.push_str(&format!("%{:02X}", byte)),
+    }
+  }
+  encoded
+}
+
+/// Calls `f` on each of `items` on up to `concurrency` threads. Results are
+/// in the same order as `items`.
+pub(crate) fn parallel_map<T, R, F>(items: &[T], concurrency: usize, f: F) -> Vec<R>
+where
+  T: Sync,
+  R: Send,
+  F: Fn(&T) -> R + Sync,
+{
+  let next = AtomicUsize::new(0);
+  let workers = concurrency.max(1).min(items.len());
+  let mut results: Vec<(usize, R)> = thread::scope(|scope| {
+    let handles: Vec<_> = (0..workers)
+      .map(|_| {
+        scope.spawn(|| {
+          let mut done = vec![];
+          loop {
+            let index = next.fetch_add(1, Ordering::Relaxed);
+            match items.get(index) {
+              Some(item) => done.push((index, f(item))),
+              None => return done,
+            }
+          }
+        })
+      })
+      .collect();
+    handles
+      .into_iter()
+      .flat_map(|handle| handle.join().expect("parallel worker thread"))
+      .collect()
+  });
+  results.sort_by_key(|(index, _)| *index);
+  results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_datetime() {
+    let expected = "2017-03-14T12:34:56.765432100+00:00";
+    assert_eq!(parse_datetime("2017-03-14T12:34:56.7654321").unwrap().to_rfc3339(), expected);
+    assert_eq!(parse_datetime("2017-03-14T12:34:56.7654321Z").unwrap().to_rfc3339(), expected);
+    assert_eq!(
+      parse_datetime("2017-03-14T05:34:56.7654321-07:00").unwrap().to_rfc3339(),
+      expected
+    );
+    assert_eq!(
+      parse_datetime("2017-03-14T12:34:56").unwrap().to_rfc3339(),
+      "2017-03-14T12:34:56+00:00"
+    );
+    assert_eq!(parse_datetime("2017-03-14"), None);
+  }
+}
@@ -0,0 +1,94 @@
+//! Types for order and return notifications pushed by Jet
+//!
+//! This crate doesn't receive notifications itself. Pass the raw request
+//! body from your HTTP handler to `parse_webhook`. Jet doesn't sign
+//! notification payloads, so authenticate the endpoint some other way, e.g.
+//! with a secret in the callback URL, and fetch the order or return with
+//! the client before acting on it.
+
+use chrono::{DateTime, Utc};
+use crate::error::*;
+use crate::orders::OrderStatus;
+use crate::utils::deserialize_datetime_opt;
+
+/// A notification from Jet, tagged by its `event_type`. Event types this
+/// crate doesn't know about are parsed as `Unknown`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "event_type", rename_all = "snake_case")]
+pub enum WebhookEvent {
+  /// An order moved to a new status, e.g. from 'created' to 'ready'
+  OrderStatusChange {
+    merchant_order_id: String,
+    status: OrderStatus,
+    /// Path to pass to `Client::get_order_detail`
+    order_url: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_datetime_opt")]
+    event_time: Option<DateTime<Utc>>,
+  },
+  /// A customer created a return, see `Client::get_return_detail`
+  ReturnCreated {
+    merchant_return_authorization_id: String,
+    merchant_order_id: String,
+    return_url: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_datetime_opt")]
+    event_time: Option<DateTime<Utc>>,
+  },
+  /// A refund was accepted or rejected, see `Client::get_refund`
+  RefundStatusChange {
+    refund_authorization_id: String,
+    merchant_order_id: Option<String>,
+    refund_status: String,
+    #[serde(default, deserialize_with = "deserialize_datetime_opt")]
+    event_time: Option<DateTime<Utc>>,
+  },
+  #[serde(other)]
+  Unknown,
+}
+
+impl WebhookEvent {
+  /// The merchant order the event is about, if any
+  pub fn merchant_order_id(&self) -> Option<&str> {
+    match *self {
+      WebhookEvent::OrderStatusChange { ref merchant_order_id, .. }
+      | WebhookEvent::ReturnCreated { ref merchant_order_id, .. } => Some(merchant_order_id),
+      WebhookEvent::RefundStatusChange { ref merchant_order_id, .. } => merchant_order_id.as_deref(),
+      WebhookEvent::Unknown => None,
+    }
+  }
+}
+
+/// Parses the body of a notification request
+pub fn parse_webhook(body: &[u8]) -> Result<WebhookEvent> {
+  Ok(serde_json::from_slice(body)?)
+}
+
+#[test]
+fn test_parse_webhook() {
+  let event = parse_webhook(br#"{
+    "event_type": "order_status_change",
+    "merchant_order_id": "2ab4c8b414124f0fa04072d615ec0610",
+    "status": "ready",
+    "order_url": "/orders/withoutShipmentDetail/2ab4c8b414124f0fa04072d615ec0610",
+    "event_time": "2017-03-14T12:34:56.7654321Z"
+  }"#).unwrap();
+  match event {
+    WebhookEvent::OrderStatusChange { ref status, ref event_time, .. } => {
+      assert_eq!(*status, OrderStatus::Ready);
+      assert!(event_time.is_some());
+    }
+    ref other => panic!("unexpected event: {:?}", other),
+  }
+  assert_eq!(event.merchant_order_id(), Some("2ab4c8b414124f0fa04072d615ec0610"));
+
+  let event = parse_webhook(br#"{
+    "event_type": "return_created",
+    "merchant_return_authorization_id": "6c1c3b6e1a0b4c5e8f7d9a2b3c4d5e6f",
+    "merchant_order_id": "2ab4c8b414124f0fa04072d615ec0610"
+  }"#).unwrap();
+  assert!(matches!(event, WebhookEvent::ReturnCreated { return_url: None, .. }));
+
+  let event = parse_webhook(br#"{"event_type": "sku_status_change", "merchant_sku": "test_product"}"#).unwrap();
+  assert_eq!(event, WebhookEvent::Unknown);
+
+  assert!(matches!(parse_webhook(b"not json"), Err(Error::Json(_))));
+}